@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::Supplies;
+
+// Remembers which crates the supplies started with so that every later state
+// can be checked against it.
+pub struct Audit {
+    inventory: HashMap<char, usize>,
+}
+
+impl Audit {
+    pub fn new(supplies: &Supplies) -> Self {
+        Audit {
+            inventory: inventory(supplies),
+        }
+    }
+
+    // Fails if any crate was created or lost, otherwise returns the (1-indexed)
+    // stacks that are currently empty.
+    pub fn check(&self, supplies: &Supplies) -> Result<Vec<usize>> {
+        let current = inventory(supplies);
+        let mut created = difference(&current, &self.inventory);
+        let mut lost = difference(&self.inventory, &current);
+        if !created.is_empty() || !lost.is_empty() {
+            created.sort();
+            lost.sort();
+            return Err(anyhow!(
+                "Expected {} crates but found {}: created {:?}, lost {:?}",
+                self.inventory.values().sum::<usize>(),
+                current.values().sum::<usize>(),
+                created,
+                lost
+            ));
+        }
+        Ok(supplies
+            .stacks
+            .iter()
+            .enumerate()
+            .filter(|(_, stack)| stack.is_empty())
+            .map(|(index, _)| index + 1)
+            .collect())
+    }
+}
+
+fn inventory(supplies: &Supplies) -> HashMap<char, usize> {
+    let mut inventory = HashMap::new();
+    supplies
+        .stacks
+        .iter()
+        .flatten()
+        .for_each(|&crate_| *inventory.entry(crate_).or_insert(0) += 1);
+    inventory
+}
+
+// Crates (with multiplicity) present in `left` but not in `right`.
+fn difference(left: &HashMap<char, usize>, right: &HashMap<char, usize>) -> Vec<(char, usize)> {
+    left.iter()
+        .filter_map(|(&crate_, &count)| {
+            let other = right.get(&crate_).copied().unwrap_or(0);
+            (count > other).then_some((crate_, count - other))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crane::CrateMoverVersion, test_stack_init};
+
+    #[test]
+    fn reports_empty_stacks() {
+        let mut supplies = test_stack_init();
+        let audit = Audit::new(&supplies);
+        assert_eq!(audit.check(&supplies).unwrap(), Vec::<usize>::new());
+        supplies
+            .move_crates(2, 1, 3, &mut CrateMoverVersion::V9001)
+            .unwrap();
+        assert_eq!(audit.check(&supplies).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn fails_when_crates_appear_or_vanish() {
+        let mut supplies = test_stack_init();
+        let audit = Audit::new(&supplies);
+        supplies.stacks[0].push('X');
+        assert!(audit.check(&supplies).is_err());
+        supplies.stacks[0].pop();
+        supplies.stacks[1].pop();
+        let error = audit.check(&supplies).unwrap_err().to_string();
+        assert!(error.contains("lost [('D', 1)]"), "{}", error);
+    }
+}
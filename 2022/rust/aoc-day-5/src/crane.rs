@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+// A crane picks up a block of crates (bottom to top) and rearranges it into the
+// order the crates end up in on the target stack. It returns the cost of the
+// move in whatever unit the model charges.
+pub trait Crane {
+    fn name(&self) -> String;
+    fn arrange(&mut self, crates: &mut Vec<char>) -> usize;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CrateMoverVersion {
+    V9000,
+    V9001,
+}
+
+impl FromStr for CrateMoverVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<Self> {
+        match version {
+            "9000" | "V9000" => Ok(CrateMoverVersion::V9000),
+            "9001" | "V9001" => Ok(CrateMoverVersion::V9001),
+            version => Err(anyhow!("Unknown CrateMover version: {}", version)),
+        }
+    }
+}
+
+impl Crane for CrateMoverVersion {
+    fn name(&self) -> String {
+        match self {
+            CrateMoverVersion::V9000 => "CrateMover 9000".to_string(),
+            CrateMoverVersion::V9001 => "CrateMover 9001".to_string(),
+        }
+    }
+
+    fn arrange(&mut self, crates: &mut Vec<char>) -> usize {
+        match self {
+            CrateMoverVersion::V9000 => {
+                crates.reverse();
+                crates.len()
+            }
+            CrateMoverVersion::V9001 => 1,
+        }
+    }
+}
+
+// Lifts at most `capacity` crates at a time, taking them from the top of the
+// source stack, so each chunk keeps its order but the chunks end up reversed.
+#[derive(Debug)]
+pub struct CapacityCrane {
+    pub capacity: usize,
+}
+
+impl Crane for CapacityCrane {
+    fn name(&self) -> String {
+        format!("Capacity crane ({})", self.capacity)
+    }
+
+    fn arrange(&mut self, crates: &mut Vec<char>) -> usize {
+        let capacity = self.capacity.max(1);
+        let lifts = crates.len().div_ceil(capacity);
+        *crates = crates.rchunks(capacity).flatten().cloned().collect();
+        lifts
+    }
+}
+
+// Moves whole batches at once, but every second batch comes out upside down.
+#[derive(Debug, Default)]
+pub struct AlternatingCrane {
+    batches: usize,
+}
+
+impl Crane for AlternatingCrane {
+    fn name(&self) -> String {
+        "Alternating crane".to_string()
+    }
+
+    fn arrange(&mut self, crates: &mut Vec<char>) -> usize {
+        if self.batches % 2 == 1 {
+            crates.reverse();
+        }
+        self.batches += 1;
+        1
+    }
+}
+
+// Moves whole batches at once, but takes longer the heavier the batch is.
+// Crate 'A' weighs 1 and crate 'Z' weighs 26.
+#[derive(Debug)]
+pub struct WeightedCrane;
+
+impl Crane for WeightedCrane {
+    fn name(&self) -> String {
+        "Weighted crane".to_string()
+    }
+
+    fn arrange(&mut self, crates: &mut Vec<char>) -> usize {
+        crates.iter().map(|&crate_| weight(crate_)).sum()
+    }
+}
+
+fn weight(crate_: char) -> usize {
+    if crate_.is_ascii_alphabetic() {
+        (crate_.to_ascii_uppercase() as u8 - b'A') as usize + 1
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrange(crane: &mut dyn Crane, crates: &str) -> (String, usize) {
+        let mut crates: Vec<char> = crates.chars().collect();
+        let cost = crane.arrange(&mut crates);
+        (crates.into_iter().collect(), cost)
+    }
+
+    #[test]
+    fn crate_movers() {
+        assert_eq!(
+            arrange(&mut CrateMoverVersion::V9000, "ABC"),
+            ("CBA".to_string(), 3)
+        );
+        assert_eq!(
+            arrange(&mut CrateMoverVersion::V9001, "ABC"),
+            ("ABC".to_string(), 1)
+        );
+        assert!(matches!("9000".parse(), Ok(CrateMoverVersion::V9000)));
+        assert!(matches!("V9001".parse(), Ok(CrateMoverVersion::V9001)));
+        assert!("9002".parse::<CrateMoverVersion>().is_err());
+    }
+
+    #[test]
+    fn capacity_crane_lifts_chunks_from_the_top() {
+        let mut crane = CapacityCrane { capacity: 2 };
+        assert_eq!(arrange(&mut crane, "ABCDE"), ("DEBCA".to_string(), 3));
+        let mut crane = CapacityCrane { capacity: 0 };
+        assert_eq!(arrange(&mut crane, "AB"), ("BA".to_string(), 2));
+    }
+
+    #[test]
+    fn alternating_crane_flips_every_second_batch() {
+        let mut crane = AlternatingCrane::default();
+        assert_eq!(arrange(&mut crane, "AB"), ("AB".to_string(), 1));
+        assert_eq!(arrange(&mut crane, "AB"), ("BA".to_string(), 1));
+        assert_eq!(arrange(&mut crane, "AB"), ("AB".to_string(), 1));
+    }
+
+    #[test]
+    fn weighted_crane_charges_by_letter() {
+        assert_eq!(arrange(&mut WeightedCrane, "AzB"), ("AzB".to_string(), 29));
+        assert_eq!(arrange(&mut WeightedCrane, "1"), ("1".to_string(), 1));
+    }
+}
@@ -1,49 +1,133 @@
 use std::{
+    env, fmt,
     fs::File,
     io::{BufRead, BufReader},
     path::Path,
+    str::FromStr,
 };
 
 use anyhow::{anyhow, Ok, Result};
+use audit::Audit;
+use crane::{AlternatingCrane, CapacityCrane, Crane, CrateMoverVersion, WeightedCrane};
+
+mod audit;
+mod crane;
+mod optimiser;
+
+const EMPTY_STACK: char = '-';
 
 fn main() {
-    part1().unwrap();
-    part2().unwrap();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let audit = args.iter().any(|arg| arg == "--audit");
+    let args: Vec<&str> = args
+        .iter()
+        .map(|arg| arg.as_str())
+        .filter(|&arg| arg != "--audit")
+        .collect();
+    match args.first() {
+        Some(&"optimise") => optimise(
+            args.get(1).unwrap_or(&"9001"),
+            args.get(2).unwrap_or(&"instructions.txt"),
+        )
+        .unwrap(),
+        _ => {
+            part1(audit).unwrap();
+            part2(audit).unwrap();
+            crane_models(audit).unwrap();
+        }
+    }
 }
 
-fn part1() -> Result<()> {
-    let path = Path::new("instructions.txt");
-    let file = File::open(path)?;
-    let mut stacks = stack_init();
-    BufReader::new(file).lines().for_each(|line| {
-        stacks
-            .execute_instruction(&line.unwrap(), CrateMoverVersion::V9000)
-            .unwrap()
-    });
-    println!("Part 1: {:?}", stacks.top_crates());
+fn part1(audit: bool) -> Result<()> {
+    let (top_crates, _) = run(&mut CrateMoverVersion::V9000, audit)?;
+    println!("Part 1: {:?}", top_crates);
     Ok(())
 }
 
-fn part2() -> Result<()> {
-    let path = Path::new("instructions.txt");
-    let file = File::open(path)?;
-    let mut stacks = stack_init();
-    BufReader::new(file).lines().for_each(|line| {
-        stacks
-            .execute_instruction(&line.unwrap(), CrateMoverVersion::V9001)
-            .unwrap()
-    });
-    println!("Part 2: {:?}", stacks.top_crates());
+fn part2(audit: bool) -> Result<()> {
+    let (top_crates, _) = run(&mut CrateMoverVersion::V9001, audit)?;
+    println!("Part 2: {:?}", top_crates);
+    Ok(())
+}
+
+fn crane_models(audit: bool) -> Result<()> {
+    let mut cranes: Vec<Box<dyn Crane>> = vec![
+        Box::new(CrateMoverVersion::V9000),
+        Box::new(CrateMoverVersion::V9001),
+        Box::new(CapacityCrane { capacity: 3 }),
+        Box::new(AlternatingCrane::default()),
+        Box::new(WeightedCrane),
+    ];
+    for crane in cranes.iter_mut() {
+        let (top_crates, cost) = run(crane.as_mut(), audit)?;
+        println!("{}: {:?} (cost {})", crane.name(), top_crates, cost);
+    }
     Ok(())
 }
 
-// fn test_stack_init() -> Supplies {
-//     let mut stacks = vec![];
-//     stacks.push(vec!['Z', 'N']);
-//     stacks.push(vec!['M', 'C', 'D']);
-//     stacks.push(vec!['P']);
-//     Supplies { stacks }
-// }
+fn optimise(version: &str, path: &str) -> Result<()> {
+    let version: CrateMoverVersion = version.parse()?;
+    let instructions = read_instructions(Path::new(path))?;
+    let optimised = optimiser::optimise(&stack_init(), &instructions, version)?;
+    optimised
+        .iter()
+        .for_each(|instruction| println!("{}", instruction));
+    println!(
+        "Removed {} of {} moves for {}",
+        instructions.len() - optimised.len(),
+        instructions.len(),
+        version.name()
+    );
+    Ok(())
+}
+
+fn run(crane: &mut dyn Crane, audit: bool) -> Result<(String, usize)> {
+    let mut stacks = stack_init();
+    let auditor = audit.then(|| Audit::new(&stacks));
+    let mut empty_stacks = vec![];
+    let mut cost = 0;
+    for (step, instruction) in read_instructions(Path::new("instructions.txt"))?
+        .iter()
+        .enumerate()
+    {
+        cost += stacks.apply(instruction, crane)?;
+        if let Some(auditor) = &auditor {
+            let empty = auditor.check(&stacks).map_err(|err| {
+                err.context(format!(
+                    "Audit failed after move {} ({})",
+                    step + 1,
+                    instruction
+                ))
+            })?;
+            if empty != empty_stacks {
+                println!(
+                    "After move {} ({}): empty stacks {:?}",
+                    step + 1,
+                    instruction,
+                    empty
+                );
+                empty_stacks = empty;
+            }
+        }
+    }
+    Ok((stacks.top_crates_label(), cost))
+}
+
+fn read_instructions(path: &Path) -> Result<Vec<Instruction>> {
+    let file = File::open(path)?;
+    BufReader::new(file)
+        .lines()
+        .map(|line| line?.parse())
+        .collect()
+}
+
+// The example stacks from the puzzle, shared by the tests.
+#[cfg(test)]
+fn test_stack_init() -> Supplies {
+    Supplies {
+        stacks: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
+    }
+}
 
 fn stack_init() -> Supplies {
     let stacks = vec![
@@ -60,86 +144,100 @@ fn stack_init() -> Supplies {
     Supplies { stacks }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Supplies {
     stacks: Vec<Vec<char>>,
 }
 
-enum CrateMoverVersion {
-    V9000,
-    V9001,
-}
-
 impl Supplies {
-    fn move_crate(&mut self, source: usize, target: usize) -> Result<()> {
-        let source_stack = self
-            .stacks
-            .get_mut(source - 1)
-            .ok_or_else(|| anyhow!("Invalid source stack index"))?;
-        let crate_ = source_stack.pop().unwrap();
-        let target_stack = self
-            .stacks
-            .get_mut(target - 1)
-            .ok_or_else(|| anyhow!("Invalid destination stack index"))?;
-        target_stack.push(crate_);
-        Ok(())
-    }
-
-    fn move_multiple_crates(
+    fn move_crates(
         &mut self,
         count: usize,
         source: usize,
         target: usize,
-        version: CrateMoverVersion,
-    ) -> Result<()> {
-        match version {
-            CrateMoverVersion::V9000 => {
-                for _ in 0..count {
-                    self.move_crate(source, target)?;
-                }
-            }
-            CrateMoverVersion::V9001 => {
-                let source_stack = self
-                    .stacks
-                    .get_mut(source - 1)
-                    .ok_or_else(|| anyhow!("Invalid source stack index"))?;
-                let length = source_stack.len();
-                let mut crates: Vec<char> = source_stack.drain(length - count..length).collect();
-                let target_stack = self
-                    .stacks
-                    .get_mut(target - 1)
-                    .ok_or_else(|| anyhow!("Invalid target stack"))?;
-                target_stack.append(&mut crates);
-            }
+        crane: &mut dyn Crane,
+    ) -> Result<usize> {
+        if target == 0 || target > self.stacks.len() {
+            return Err(anyhow!("Invalid target stack index"));
+        }
+        let source_stack = self
+            .stacks
+            .get_mut(source.wrapping_sub(1))
+            .ok_or_else(|| anyhow!("Invalid source stack index"))?;
+        let length = source_stack.len();
+        if count > length {
+            return Err(anyhow!(
+                "Cannot move {} crates from stack {} holding {}",
+                count,
+                source,
+                length
+            ));
         }
-        Ok(())
+        let mut crates: Vec<char> = source_stack.drain(length - count..length).collect();
+        let cost = crane.arrange(&mut crates);
+        self.stacks[target - 1].append(&mut crates);
+        Ok(cost)
     }
 
-    fn top_crates(&self) -> String {
+    fn top_crates(&self) -> Vec<Option<char>> {
         self.stacks
             .iter()
-            .map(|stack| stack.last().unwrap().to_string())
-            .reduce(|x, y| x + &y)
-            .unwrap()
+            .map(|stack| stack.last().copied())
+            .collect()
+    }
+
+    fn top_crates_label(&self) -> String {
+        self.top_crates()
+            .into_iter()
+            .map(|crate_| crate_.unwrap_or(EMPTY_STACK))
+            .collect()
     }
 
-    fn execute_instruction(&mut self, instruction: &str, version: CrateMoverVersion) -> Result<()> {
+    fn apply(&mut self, instruction: &Instruction, crane: &mut dyn Crane) -> Result<usize> {
+        self.move_crates(
+            instruction.count,
+            instruction.source,
+            instruction.target,
+            crane,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Instruction {
+    count: usize,
+    source: usize,
+    target: usize,
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    fn from_str(instruction: &str) -> Result<Self> {
         let instruction: Vec<&str> = instruction.split(' ').collect();
-        self.move_multiple_crates(
-            instruction
+        Ok(Instruction {
+            count: instruction
                 .get(1)
                 .ok_or_else(|| anyhow!("Could not read count parameter"))?
                 .parse()?,
-            instruction
+            source: instruction
                 .get(3)
                 .ok_or_else(|| anyhow!("Could not read source parameter"))?
                 .parse()?,
-            instruction
+            target: instruction
                 .get(5)
                 .ok_or_else(|| anyhow!("Could not read target parameter"))?
                 .parse()?,
-            version,
-        )?;
-        Ok(())
+        })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count, self.source, self.target
+        )
     }
 }
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::{crane::CrateMoverVersion, Instruction, Supplies};
+
+// Searches for a shorter list of moves that leaves the stacks in exactly the
+// same final state. Every rewrite is checked by replaying the candidate list
+// from the initial stacks, so the result is always equivalent to the input.
+pub fn optimise(
+    initial: &Supplies,
+    instructions: &[Instruction],
+    version: CrateMoverVersion,
+) -> Result<Vec<Instruction>> {
+    let goal = simulate(initial, instructions, version)?;
+    let mut optimised = instructions.to_vec();
+    loop {
+        let length = optimised.len();
+        optimised = cut_cycles(initial, &optimised, version)?;
+        optimised = merge_adjacent(initial, &optimised, version)?;
+        optimised = drop_redundant(initial, &optimised, version, &goal);
+        if optimised.len() == length {
+            return Ok(optimised);
+        }
+    }
+}
+
+fn simulate(
+    initial: &Supplies,
+    instructions: &[Instruction],
+    mut version: CrateMoverVersion,
+) -> Result<Supplies> {
+    let mut supplies = initial.clone();
+    for instruction in instructions {
+        supplies.apply(instruction, &mut version)?;
+    }
+    Ok(supplies)
+}
+
+// Whenever the stacks return to a state they were already in, every move made
+// since then can be dropped.
+fn cut_cycles(
+    initial: &Supplies,
+    instructions: &[Instruction],
+    mut version: CrateMoverVersion,
+) -> Result<Vec<Instruction>> {
+    let mut supplies = initial.clone();
+    let mut seen = HashMap::from([(supplies.clone(), 0)]);
+    let mut kept = vec![];
+    for instruction in instructions {
+        supplies.apply(instruction, &mut version)?;
+        kept.push(*instruction);
+        match seen.get(&supplies) {
+            Some(&length) => {
+                kept.truncate(length);
+                seen.retain(|_, &mut seen_length| seen_length <= length);
+            }
+            None => {
+                seen.insert(supplies.clone(), kept.len());
+            }
+        }
+    }
+    Ok(kept)
+}
+
+// Folds consecutive moves between the same pair of stacks into a single move
+// when that produces the same stacks.
+fn merge_adjacent(
+    initial: &Supplies,
+    instructions: &[Instruction],
+    mut version: CrateMoverVersion,
+) -> Result<Vec<Instruction>> {
+    let mut supplies = initial.clone();
+    let mut merged: Vec<Instruction> = vec![];
+    let mut before_last = supplies.clone();
+    for instruction in instructions {
+        let after = {
+            let mut after = supplies.clone();
+            after.apply(instruction, &mut version)?;
+            after
+        };
+        let candidate = merged
+            .last()
+            .filter(|last| last.source == instruction.source && last.target == instruction.target)
+            .map(|last| Instruction {
+                count: last.count + instruction.count,
+                ..*last
+            });
+        let mut folded = false;
+        if let Some(candidate) = candidate {
+            let mut check = before_last.clone();
+            if check.apply(&candidate, &mut version).is_ok() && check == after {
+                *merged.last_mut().unwrap() = candidate;
+                folded = true;
+            }
+        }
+        if !folded {
+            before_last = supplies;
+            merged.push(*instruction);
+        }
+        supplies = after;
+    }
+    Ok(merged)
+}
+
+// Tries leaving out each move in turn, keeping the omission whenever the
+// remaining moves are still valid and reach the goal.
+fn drop_redundant(
+    initial: &Supplies,
+    instructions: &[Instruction],
+    version: CrateMoverVersion,
+    goal: &Supplies,
+) -> Vec<Instruction> {
+    let mut kept = instructions.to_vec();
+    for index in (0..kept.len()).rev() {
+        let removed = kept.remove(index);
+        match simulate(initial, &kept, version) {
+            Ok(supplies) if &supplies == goal => {}
+            _ => kept.insert(index, removed),
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_stack_init;
+
+    fn instructions(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn optimised_moves_reach_the_same_stacks() {
+        let moves = instructions(&[
+            "move 1 from 2 to 1",
+            "move 3 from 1 to 3",
+            "move 2 from 2 to 1",
+            "move 1 from 1 to 2",
+            "move 1 from 2 to 3",
+            "move 1 from 3 to 2",
+        ]);
+        for version in [CrateMoverVersion::V9000, CrateMoverVersion::V9001] {
+            let optimised = optimise(&test_stack_init(), &moves, version).unwrap();
+            assert!(optimised.len() <= moves.len());
+            assert_eq!(
+                simulate(&test_stack_init(), &optimised, version).unwrap(),
+                simulate(&test_stack_init(), &moves, version).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn cycles_are_cut() {
+        let moves = instructions(&[
+            "move 1 from 1 to 2",
+            "move 1 from 2 to 1",
+            "move 1 from 3 to 1",
+        ]);
+        for version in [CrateMoverVersion::V9000, CrateMoverVersion::V9001] {
+            assert_eq!(
+                cut_cycles(&test_stack_init(), &moves, version).unwrap(),
+                instructions(&["move 1 from 3 to 1"])
+            );
+            assert_eq!(
+                optimise(&test_stack_init(), &moves, version).unwrap(),
+                instructions(&["move 1 from 3 to 1"])
+            );
+        }
+    }
+
+    #[test]
+    fn adjacent_moves_merge_only_when_equivalent() {
+        let moves = instructions(&["move 1 from 1 to 3", "move 1 from 1 to 3"]);
+        // One crate at a time reverses them, which is what the 9000 does to a
+        // batch anyway, but the 9001 would keep them in order.
+        assert_eq!(
+            merge_adjacent(&test_stack_init(), &moves, CrateMoverVersion::V9000).unwrap(),
+            instructions(&["move 2 from 1 to 3"])
+        );
+        assert_eq!(
+            merge_adjacent(&test_stack_init(), &moves, CrateMoverVersion::V9001).unwrap(),
+            moves
+        );
+    }
+
+    #[test]
+    fn moves_that_change_nothing_are_dropped() {
+        let moves = instructions(&["move 1 from 1 to 2", "move 0 from 2 to 3"]);
+        let version = CrateMoverVersion::V9001;
+        let goal = simulate(&test_stack_init(), &moves, version).unwrap();
+        assert_eq!(
+            drop_redundant(&test_stack_init(), &moves, version, &goal),
+            instructions(&["move 1 from 1 to 2"])
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use aoc_day_6::{find_marker, Marker};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const STREAM_LENGTH: usize = 4 * 1024 * 1024;
+
+// A stream drawn from `length - 1` letters can't contain a marker, so both
+// detectors have to scan all of it before reaching the one at the end.
+fn stream(length: usize) -> String {
+    let mut state: u64 = 0x2545f4914f6cdd1d;
+    let mut stream: String = (0..STREAM_LENGTH)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (b'a' + ((state >> 33) % (length as u64 - 1)) as u8) as char
+        })
+        .collect();
+    stream.push_str("abcdefghijklmnopqrstuvwxyz");
+    stream
+}
+
+fn marker_position(stream: &str, length: usize) -> Option<usize> {
+    let mut marker = Marker::new(length);
+    stream
+        .chars()
+        .position(|item| {
+            marker.add(item);
+            marker.is_valid_marker()
+        })
+        .map(|index| index + 1)
+}
+
+fn bench_detectors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("first_marker");
+    group.sample_size(10);
+    for length in [4, 14] {
+        let stream = stream(length);
+        group.throughput(Throughput::Bytes(stream.len() as u64));
+        group.bench_with_input(BenchmarkId::new("Marker", length), &stream, |b, stream| {
+            b.iter(|| marker_position(stream, length))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("Detector", length),
+            &stream,
+            |b, stream| b.iter(|| find_marker(stream.chars(), length)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_detectors);
+criterion_main!(benches);
@@ -0,0 +1,223 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    io::{self, BufReader, Read},
+};
+
+// Sliding window that keeps a count per symbol and the number of distinct
+// symbols, so each step is O(1) and nothing is allocated once every symbol in
+// the alphabet has been seen. Symbols can be anything hashable: bytes, chars
+// or whole tokens.
+pub struct Detector<T> {
+    window: VecDeque<T>,
+    counts: HashMap<T, usize>,
+    distinct: usize,
+    length: usize,
+}
+
+impl<T: Hash + Eq + Clone> Detector<T> {
+    pub fn new(length: usize) -> Self {
+        Detector {
+            window: VecDeque::with_capacity(length),
+            counts: HashMap::new(),
+            distinct: 0,
+            length,
+        }
+    }
+
+    pub fn add(&mut self, item: T) {
+        if self.length == 0 {
+            return;
+        }
+        if self.window.len() == self.length {
+            if let Some(oldest) = self.window.pop_front() {
+                let count = self.counts.entry(oldest).or_insert(1);
+                *count -= 1;
+                if *count == 0 {
+                    self.distinct -= 1;
+                }
+            }
+        }
+        self.window.push_back(item.clone());
+        let count = self.counts.entry(item).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            self.distinct += 1;
+        }
+    }
+
+    pub fn is_valid_marker(&self) -> bool {
+        self.distinct == self.length
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    pub fn duplicates(&self) -> usize {
+        self.window.len() - self.distinct
+    }
+
+    // A full window with at least `min_distinct` different symbols in it.
+    pub fn satisfies(&self, min_distinct: usize) -> bool {
+        self.window.len() == self.length && self.distinct >= min_distinct
+    }
+}
+
+// A window size together with how many distinct symbols it needs to count as
+// a marker. Strict markers need every symbol to be distinct; noisy streams can
+// allow a few repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub length: usize,
+    pub min_distinct: usize,
+}
+
+impl Window {
+    pub fn marker(length: usize) -> Self {
+        Window {
+            length,
+            min_distinct: length,
+        }
+    }
+
+    pub fn with_min_distinct(length: usize, min_distinct: usize) -> Self {
+        Window {
+            length,
+            min_distinct,
+        }
+    }
+
+    pub fn with_max_duplicates(length: usize, max_duplicates: usize) -> Self {
+        Window {
+            length,
+            min_distinct: length.saturating_sub(max_duplicates),
+        }
+    }
+}
+
+// Position just after the first window of `length` distinct symbols.
+pub fn find_marker<T: Hash + Eq + Clone>(
+    stream: impl IntoIterator<Item = T>,
+    length: usize,
+) -> Option<usize> {
+    let mut detector = Detector::new(length);
+    stream
+        .into_iter()
+        .position(|item| {
+            detector.add(item);
+            detector.is_valid_marker()
+        })
+        .map(|index| index + 1)
+}
+
+// Position just after every window of `length` distinct symbols, including
+// windows that overlap an earlier marker.
+pub fn marker_positions<T: Hash + Eq + Clone>(
+    stream: impl IntoIterator<Item = T>,
+    length: usize,
+) -> Vec<usize> {
+    window_positions(stream, Window::marker(length))
+}
+
+// Like `marker_positions`, but the window only needs as many distinct symbols
+// as it asks for.
+pub fn window_positions<T: Hash + Eq + Clone>(
+    stream: impl IntoIterator<Item = T>,
+    window: Window,
+) -> Vec<usize> {
+    let mut detector = Detector::new(window.length);
+    stream
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            detector.add(item);
+            detector.satisfies(window.min_distinct).then_some(index + 1)
+        })
+        .collect()
+}
+
+// First marker position for each of the window `lengths`, found in a single
+// pass that stops as soon as every window has produced one.
+pub fn first_markers<T: Hash + Eq + Clone>(
+    stream: impl IntoIterator<Item = T>,
+    lengths: &[usize],
+) -> Vec<Option<usize>> {
+    let windows: Vec<Window> = lengths
+        .iter()
+        .map(|&length| Window::marker(length))
+        .collect();
+    first_windows(stream, &windows)
+}
+
+// Like `first_markers`, but each window only needs as many distinct symbols as
+// it asks for.
+pub fn first_windows<T: Hash + Eq + Clone>(
+    stream: impl IntoIterator<Item = T>,
+    windows: &[Window],
+) -> Vec<Option<usize>> {
+    let mut detectors: Vec<Detector<T>> = windows
+        .iter()
+        .map(|window| Detector::new(window.length))
+        .collect();
+    let mut positions = vec![None; windows.len()];
+    let mut remaining = windows.len();
+    for (index, item) in stream.into_iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        for ((detector, window), position) in
+            detectors.iter_mut().zip(windows).zip(positions.iter_mut())
+        {
+            if position.is_some() {
+                continue;
+            }
+            detector.add(item.clone());
+            if detector.satisfies(window.min_distinct) {
+                *position = Some(index + 1);
+                remaining -= 1;
+            }
+        }
+    }
+    positions
+}
+
+// Same as `first_windows`, but pulls bytes from `reader` through a buffer so
+// arbitrarily large or binary inputs never have to be held in memory. Reading
+// stops early once every window has a marker.
+pub fn first_windows_in<R: Read>(
+    reader: R,
+    windows: &[Window],
+    skip: impl Fn(u8) -> bool,
+) -> io::Result<Vec<Option<usize>>> {
+    let mut error = None;
+    let bytes = BufReader::new(reader)
+        .bytes()
+        .map_while(|byte| byte.map_err(|err| error = Some(err)).ok())
+        .filter(|&byte| !skip(byte));
+    let positions = first_windows(bytes, windows);
+    match error {
+        Some(err) => Err(err),
+        None => Ok(positions),
+    }
+}
+
+// Start index and length of the longest stretch without a repeated symbol.
+// Ties go to the earliest stretch.
+pub fn longest_distinct_run<T: Hash + Eq>(
+    stream: impl IntoIterator<Item = T>,
+) -> Option<(usize, usize)> {
+    let mut last_seen = HashMap::new();
+    let mut start = 0;
+    let mut longest = None;
+    for (index, item) in stream.into_iter().enumerate() {
+        if let Some(previous) = last_seen.insert(item, index) {
+            start = start.max(previous + 1);
+        }
+        let length = index + 1 - start;
+        if longest.is_none_or(|(_, longest)| length > longest) {
+            longest = Some((start, length));
+        }
+    }
+    longest
+}
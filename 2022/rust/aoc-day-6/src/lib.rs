@@ -0,0 +1,13 @@
+mod detector;
+mod marker;
+mod transmission;
+
+pub use detector::{
+    find_marker, first_markers, first_windows, first_windows_in, longest_distinct_run,
+    marker_positions, window_positions, Detector, Window,
+};
+pub use marker::Marker;
+pub use transmission::{decode, frames, window_frames, Frame, Transmission};
+
+pub const START_OF_PACKET_LENGTH: usize = 4;
+pub const START_OF_MESSAGE_LENGTH: usize = 14;
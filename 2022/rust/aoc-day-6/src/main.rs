@@ -1,69 +1,164 @@
-use std::collections::HashSet;
+use aoc_day_6::{
+    first_windows, first_windows_in, longest_distinct_run, window_frames, window_positions, Window,
+    START_OF_MESSAGE_LENGTH, START_OF_PACKET_LENGTH,
+};
+use color_eyre::{eyre::eyre, Result};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Read},
+};
 
-const TEST_INPUT: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-const INPUT: &str = include_str!("../input.txt");
-const START_OF_PACKET_LENGTH: usize = 4;
-const START_OF_MESSAGE_LENGTH: usize = 14;
+// Usage: aoc-day-6 [--windows 4,14] [--min-distinct M | --max-duplicates K]
+//                  [--binary] [--longest] [--all] [path | -]
+// Reads the datastream from stdin when no path (or `-`) is given. Line breaks
+// aren't part of the stream unless `--binary` is passed, so positions count
+// only the bytes in between and aren't file offsets for multi-line input.
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let mut windows = vec![START_OF_PACKET_LENGTH, START_OF_MESSAGE_LENGTH];
+    let mut tolerance = Tolerance::None;
+    let mut all = false;
+    let mut binary = false;
+    let mut longest = false;
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--windows" | "-w" => {
+                windows = parse_windows(
+                    &args
+                        .next()
+                        .ok_or_else(|| eyre!("--windows requires a list of sizes"))?,
+                )?
+            }
+            "--min-distinct" => {
+                tolerance = Tolerance::MinDistinct(
+                    args.next()
+                        .ok_or_else(|| eyre!("--min-distinct requires a count"))?
+                        .parse()?,
+                )
+            }
+            "--max-duplicates" => {
+                tolerance = Tolerance::MaxDuplicates(
+                    args.next()
+                        .ok_or_else(|| eyre!("--max-duplicates requires a count"))?
+                        .parse()?,
+                )
+            }
+            "--all" => all = true,
+            "--binary" => binary = true,
+            "--longest" => longest = true,
+            _ => path = Some(arg),
+        }
+    }
 
-fn main() {
-    part1();
-    part2();
-}
+    let windows: Vec<Window> = windows
+        .into_iter()
+        .map(|length| tolerance.window(length))
+        .collect();
 
-fn part1() {
-    let input = INPUT.clone();
-    let mut marker = Marker::new(START_OF_PACKET_LENGTH);
-    let (index, _) = input
-        .chars()
-        .enumerate()
-        .find(|(index, item)| {
-            marker.add(*item);
-            marker.is_valid_marker()
-        })
-        .unwrap();
-    println!("{}", index + 1);
-}
+    if all || longest {
+        // Drop line breaks here too so these answers match the streaming ones.
+        let stream: Vec<u8> = read_input(path.as_deref())?
+            .into_iter()
+            .filter(|&byte| binary || (byte != b'\n' && byte != b'\r'))
+            .collect();
+        print_positions(&windows, first_windows(stream.iter().copied(), &windows));
+        if longest {
+            match longest_distinct_run(&stream) {
+                Some((start, length)) => println!(
+                    "longest distinct run: {} symbols at {}: {}",
+                    length,
+                    start,
+                    String::from_utf8_lossy(&stream[start..start + length])
+                ),
+                None => println!("longest distinct run: empty stream"),
+            }
+        }
+        if all {
+            all_markers(&stream, &windows);
+        }
+        return Ok(());
+    }
 
-fn part2() {
-    let input = INPUT.clone();
-    let mut marker = Marker::new(START_OF_MESSAGE_LENGTH);
-    let (index, _) = input
-        .chars()
-        .enumerate()
-        .find(|(index, item)| {
-            marker.add(*item);
-            marker.is_valid_marker()
-        })
-        .unwrap();
-    println!("{}", index + 1);
+    let reader: Box<dyn Read> = match path.as_deref() {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(File::open(path)?),
+    };
+    let skip = |byte: u8| !binary && (byte == b'\n' || byte == b'\r');
+    print_positions(&windows, first_windows_in(reader, &windows, skip)?);
+    Ok(())
 }
 
-struct Marker {
-    last_four: Vec<char>,
-    max_length: usize,
+enum Tolerance {
+    None,
+    MinDistinct(usize),
+    MaxDuplicates(usize),
 }
 
-impl Marker {
-    fn new(max_length: usize) -> Self {
-        Marker {
-            last_four: vec![],
-            max_length,
+impl Tolerance {
+    fn window(&self, length: usize) -> Window {
+        match self {
+            Tolerance::None => Window::marker(length),
+            Tolerance::MinDistinct(min_distinct) => {
+                Window::with_min_distinct(length, *min_distinct)
+            }
+            Tolerance::MaxDuplicates(max_duplicates) => {
+                Window::with_max_duplicates(length, *max_duplicates)
+            }
         }
     }
+}
+
+fn print_positions(windows: &[Window], positions: Vec<Option<usize>>) {
+    windows
+        .iter()
+        .zip(positions)
+        .for_each(|(window, position)| match position {
+            Some(position) => println!("{}: {}", window.length, position),
+            None => println!("{}: no marker", window.length),
+        });
+}
 
-    fn add(&mut self, item: char) {
-        self.last_four.push(item);
-        if self.last_four.len() > self.max_length {
-            self.last_four.remove(0);
+fn parse_windows(windows: &str) -> Result<Vec<usize>> {
+    windows
+        .split(',')
+        .map(|window| match window.trim().parse() {
+            Ok(0) => Err(eyre!("Window size must be at least 1")),
+            Ok(window) => Ok(window),
+            Err(_) => Err(eyre!("Invalid window size: {}", window)),
+        })
+        .collect()
+}
+
+fn read_input(path: Option<&str>) -> Result<Vec<u8>> {
+    match path {
+        None | Some("-") => {
+            let mut input = vec![];
+            io::stdin().read_to_end(&mut input)?;
+            Ok(input)
         }
+        Some(path) => Ok(fs::read(path)?),
     }
+}
 
-    fn is_valid_marker(&self) -> bool {
-        self.last_four
-            .clone()
-            .into_iter()
-            .collect::<HashSet<char>>()
-            .len()
-            == self.max_length
+fn all_markers(stream: &[u8], windows: &[Window]) {
+    for &window in windows {
+        println!(
+            "{} positions: {:?}",
+            window.length,
+            window_positions(stream.iter().copied(), window)
+        );
+    }
+    for &window in windows {
+        window_frames(stream, window).iter().for_each(|frame| {
+            println!(
+                "{} frame at {}: {}",
+                window.length,
+                frame.marker,
+                String::from_utf8_lossy(frame.payload)
+            )
+        });
     }
 }
@@ -0,0 +1,31 @@
+use std::collections::HashSet;
+
+pub struct Marker {
+    last_four: Vec<char>,
+    max_length: usize,
+}
+
+impl Marker {
+    pub fn new(max_length: usize) -> Self {
+        Marker {
+            last_four: vec![],
+            max_length,
+        }
+    }
+
+    pub fn add(&mut self, item: char) {
+        self.last_four.push(item);
+        if self.last_four.len() > self.max_length {
+            self.last_four.remove(0);
+        }
+    }
+
+    pub fn is_valid_marker(&self) -> bool {
+        self.last_four
+            .clone()
+            .into_iter()
+            .collect::<HashSet<char>>()
+            .len()
+            == self.max_length
+    }
+}
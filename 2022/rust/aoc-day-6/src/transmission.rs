@@ -0,0 +1,58 @@
+use std::hash::Hash;
+
+use crate::{Detector, Window, START_OF_MESSAGE_LENGTH, START_OF_PACKET_LENGTH};
+
+// The data following a marker, up to where the next marker begins. Positions
+// count symbols, the same as everywhere else in the crate.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Frame<'a, T> {
+    pub marker: usize,
+    pub payload: &'a [T],
+}
+
+#[derive(Debug)]
+pub struct Transmission<'a, T> {
+    pub packets: Vec<Frame<'a, T>>,
+    pub messages: Vec<Frame<'a, T>>,
+}
+
+pub fn decode<T: Hash + Eq + Clone>(stream: &[T]) -> Transmission<'_, T> {
+    Transmission {
+        packets: frames(stream, START_OF_PACKET_LENGTH),
+        messages: frames(stream, START_OF_MESSAGE_LENGTH),
+    }
+}
+
+// Splits the stream at non-overlapping markers: once a marker is found the
+// search starts over with the symbols that follow it.
+pub fn frames<T: Hash + Eq + Clone>(stream: &[T], length: usize) -> Vec<Frame<'_, T>> {
+    window_frames(stream, Window::marker(length))
+}
+
+// Like `frames`, but a marker only needs as many distinct symbols as `window`
+// asks for.
+pub fn window_frames<T: Hash + Eq + Clone>(stream: &[T], window: Window) -> Vec<Frame<'_, T>> {
+    let mut detector = Detector::new(window.length);
+    let mut markers = vec![];
+    for (index, item) in stream.iter().enumerate() {
+        detector.add(item.clone());
+        if detector.satisfies(window.min_distinct) {
+            markers.push(index + 1);
+            detector = Detector::new(window.length);
+        }
+    }
+    markers
+        .iter()
+        .enumerate()
+        .map(|(index, &marker)| {
+            let end = markers
+                .get(index + 1)
+                .map(|next| next - window.length)
+                .unwrap_or(stream.len());
+            Frame {
+                marker,
+                payload: &stream[marker..end],
+            }
+        })
+        .collect()
+}
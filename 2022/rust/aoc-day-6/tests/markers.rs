@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+
+use aoc_day_6::{
+    decode, find_marker, first_markers, first_windows, first_windows_in, frames,
+    longest_distinct_run, marker_positions, window_frames, window_positions, Marker, Window,
+    START_OF_MESSAGE_LENGTH, START_OF_PACKET_LENGTH,
+};
+use proptest::prelude::*;
+
+// The example datastreams from the puzzle with their start-of-packet and
+// start-of-message positions.
+const EXAMPLES: [(&str, usize, usize); 5] = [
+    ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+    ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+    ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+    ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+    ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+];
+
+fn brute_force_windows(stream: &[u8], length: usize, min_distinct: usize) -> Vec<usize> {
+    if length == 0 || stream.len() < length {
+        return vec![];
+    }
+    stream
+        .windows(length)
+        .enumerate()
+        .filter(|(_, window)| window.iter().collect::<HashSet<_>>().len() >= min_distinct)
+        .map(|(index, _)| index + length)
+        .collect()
+}
+
+fn brute_force_longest_run(stream: &[u8]) -> Option<(usize, usize)> {
+    let mut longest: Option<(usize, usize)> = None;
+    for start in 0..stream.len() {
+        let mut seen = HashSet::new();
+        let length = stream[start..]
+            .iter()
+            .take_while(|&&item| seen.insert(item))
+            .count();
+        if longest.is_none_or(|(_, longest)| length > longest) {
+            longest = Some((start, length));
+        }
+    }
+    longest
+}
+
+fn marker_position(stream: &[u8], length: usize) -> Option<usize> {
+    let mut marker = Marker::new(length);
+    stream
+        .iter()
+        .position(|&item| {
+            marker.add(item as char);
+            marker.is_valid_marker()
+        })
+        .map(|index| index + 1)
+}
+
+#[test]
+fn examples() {
+    for (stream, packet, message) in EXAMPLES {
+        assert_eq!(
+            find_marker(stream.chars(), START_OF_PACKET_LENGTH),
+            Some(packet),
+            "start-of-packet in {}",
+            stream
+        );
+        assert_eq!(
+            find_marker(stream.chars(), START_OF_MESSAGE_LENGTH),
+            Some(message),
+            "start-of-message in {}",
+            stream
+        );
+        assert_eq!(
+            first_markers(
+                stream.bytes(),
+                &[START_OF_PACKET_LENGTH, START_OF_MESSAGE_LENGTH]
+            ),
+            vec![Some(packet), Some(message)],
+            "single pass over {}",
+            stream
+        );
+        assert_eq!(
+            first_windows_in(
+                stream.as_bytes(),
+                &[
+                    Window::marker(START_OF_PACKET_LENGTH),
+                    Window::marker(START_OF_MESSAGE_LENGTH)
+                ],
+                |_| false
+            )
+            .unwrap(),
+            vec![Some(packet), Some(message)],
+            "reader over {}",
+            stream
+        );
+        assert_eq!(
+            marker_position(stream.as_bytes(), START_OF_PACKET_LENGTH),
+            Some(packet),
+            "Marker start-of-packet in {}",
+            stream
+        );
+        assert_eq!(
+            marker_position(stream.as_bytes(), START_OF_MESSAGE_LENGTH),
+            Some(message),
+            "Marker start-of-message in {}",
+            stream
+        );
+    }
+}
+
+#[test]
+fn reader_skips_line_breaks() {
+    let (stream, packet, message) = EXAMPLES[0];
+    let input = format!("{}\r\n", stream);
+    let windows = [
+        Window::marker(START_OF_PACKET_LENGTH),
+        Window::marker(START_OF_MESSAGE_LENGTH),
+    ];
+    assert_eq!(
+        first_windows_in(input.as_bytes(), &windows, |byte| byte == b'\n'
+            || byte == b'\r')
+        .unwrap(),
+        vec![Some(packet), Some(message)]
+    );
+}
+
+#[test]
+fn frames_follow_their_markers() {
+    let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+    let frames = frames(stream.as_bytes(), START_OF_MESSAGE_LENGTH);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].marker, 19);
+    assert_eq!(frames[0].payload, b"jfqwrcgsmlb");
+}
+
+#[test]
+fn frames_end_where_the_next_marker_starts() {
+    // Markers at 4, 10 and 16, each search starting after the last marker.
+    let stream = b"abcdxxwxyzqqqrstuvv";
+    let frames = frames(stream, 4);
+    let found: Vec<(usize, &[u8])> = frames
+        .iter()
+        .map(|frame| (frame.marker, frame.payload))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(4, &b"xx"[..]), (10, &b"qq"[..]), (16, &b"uvv"[..])]
+    );
+}
+
+#[test]
+fn tolerant_frames_accept_repeats() {
+    let stream = b"aabcxxyzqrrs";
+    let strict: Vec<usize> = frames(stream, 4).iter().map(|frame| frame.marker).collect();
+    assert_eq!(strict, vec![5, 9]);
+    let tolerant = window_frames(stream, Window::with_max_duplicates(4, 1));
+    let found: Vec<(usize, &[u8])> = tolerant
+        .iter()
+        .map(|frame| (frame.marker, frame.payload))
+        .collect();
+    assert_eq!(found, vec![(4, &b""[..]), (8, &b""[..]), (12, &b""[..])]);
+}
+
+#[test]
+fn frame_positions_count_symbols() {
+    // Every symbol here is two bytes in UTF-8, so positions in chars and in
+    // bytes would differ.
+    let stream: Vec<char> = "ééàüöüäëïô".chars().collect();
+    let frames = frames(&stream, 4);
+    let found: Vec<(usize, &[char])> = frames
+        .iter()
+        .map(|frame| (frame.marker, frame.payload))
+        .collect();
+    assert_eq!(found, vec![(5, &[][..]), (9, &['ô'][..])]);
+    assert_eq!(
+        marker_positions(stream.iter().copied(), 4).first(),
+        Some(&5)
+    );
+}
+
+#[test]
+fn decode_splits_packets_and_messages() {
+    let (stream, packet, message) = EXAMPLES[0];
+    let transmission = decode(stream.as_bytes());
+    let markers: Vec<usize> = transmission
+        .packets
+        .iter()
+        .map(|frame| frame.marker)
+        .collect();
+    assert_eq!(markers, vec![packet, 11, 15, 19, 23, 27]);
+    assert_eq!(transmission.packets[5].payload, b"mlb");
+    assert_eq!(transmission.messages.len(), 1);
+    assert_eq!(transmission.messages[0].marker, message);
+    assert_eq!(transmission.messages[0].payload, b"jfqwrcgsmlb");
+}
+
+proptest! {
+    #[test]
+    fn detector_matches_brute_force(
+        stream in proptest::collection::vec(0u8..6, 0..200),
+        length in 1usize..8,
+    ) {
+        let expected = brute_force_windows(&stream, length, length);
+        prop_assert_eq!(find_marker(stream.iter().copied(), length), expected.first().copied());
+        prop_assert_eq!(marker_positions(stream.iter().copied(), length), expected.clone());
+        prop_assert_eq!(marker_position(&stream, length), expected.first().copied());
+    }
+
+    #[test]
+    fn single_pass_matches_brute_force(
+        stream in proptest::collection::vec(0u8..10, 0..200),
+        lengths in proptest::collection::vec(1usize..12, 1..5),
+    ) {
+        let expected: Vec<Option<usize>> = lengths
+            .iter()
+            .map(|&length| brute_force_windows(&stream, length, length).first().copied())
+            .collect();
+        prop_assert_eq!(first_markers(stream.iter().copied(), &lengths), expected);
+    }
+
+    #[test]
+    fn tolerant_windows_match_brute_force(
+        stream in proptest::collection::vec(0u8..6, 0..200),
+        length in 1usize..10,
+        max_duplicates in 0usize..4,
+    ) {
+        let window = Window::with_max_duplicates(length, max_duplicates);
+        let expected = brute_force_windows(&stream, length, window.min_distinct)
+            .first()
+            .copied();
+        prop_assert_eq!(first_windows(stream.iter().copied(), &[window]), vec![expected]);
+    }
+
+    #[test]
+    fn tolerant_positions_match_brute_force(
+        stream in proptest::collection::vec(0u8..6, 0..200),
+        length in 1usize..10,
+        max_duplicates in 0usize..4,
+    ) {
+        let window = Window::with_max_duplicates(length, max_duplicates);
+        prop_assert_eq!(
+            window_positions(stream.iter().copied(), window),
+            brute_force_windows(&stream, length, window.min_distinct)
+        );
+    }
+
+    #[test]
+    fn longest_run_matches_brute_force(stream in proptest::collection::vec(0u8..8, 0..200)) {
+        prop_assert_eq!(
+            longest_distinct_run(stream.iter().copied()),
+            brute_force_longest_run(&stream)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use aoc_day_7::{Filesystem, FsItem, Shell};
+use common::Random;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+const ENTRIES: usize = 100_000;
+
+// A transcript that lists at least `ENTRIES` files and directories, descending
+// into subdirectories until it reaches `max_depth` and then backing out again.
+fn transcript(max_depth: usize) -> Vec<String> {
+    let mut random = Random(0x2545f4914f6cdd1d);
+    let mut lines = vec!["$ cd /".to_string()];
+    let mut pending: Vec<Vec<String>> = vec![];
+    let mut entries = 0;
+    let mut names = 0;
+    while entries < ENTRIES {
+        let depth = pending.len();
+        match pending.last_mut() {
+            Some(children) if children.is_empty() && depth > 1 => {
+                pending.pop();
+                lines.push("$ cd ..".to_string());
+                continue;
+            }
+            Some(children) if depth < max_depth && !children.is_empty() => {
+                let child = children.pop().unwrap();
+                lines.push(format!("$ cd {}", child));
+            }
+            _ => {
+                // Either the root has run out of subdirectories or we're as deep
+                // as allowed, so list more entries here instead of descending.
+                pending.pop();
+            }
+        }
+        lines.push("$ ls".to_string());
+        let mut children = vec![];
+        for _ in 0..random.below(8) + 1 {
+            names += 1;
+            if random.below(4) == 0 {
+                children.push(format!("d{}", names));
+                lines.push(format!("dir d{}", names));
+            } else {
+                lines.push(format!("{} f{}.txt", random.below(300_000) + 1, names));
+            }
+            entries += 1;
+        }
+        pending.push(children);
+    }
+    lines
+}
+
+fn replay(transcript: &[String]) -> Filesystem {
+    let mut shell = Shell::new();
+    for line in transcript {
+        shell.execute_command(line).unwrap();
+    }
+    shell.fs
+}
+
+// What `directory_sizes` used to do: add up every file under each directory
+// separately, which is quadratic in the depth of the tree.
+fn recomputed_directory_sizes(fs: &Filesystem) -> Vec<usize> {
+    fs.directories()
+        .into_iter()
+        .map(|directory| {
+            fs.walk_from(directory)
+                .into_iter()
+                .filter_map(|id| match fs.node(id).item {
+                    FsItem::File(size) => Some(size),
+                    FsItem::Directory(_) => None,
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn bench_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("directory_sizes");
+    group.sample_size(10);
+    for max_depth in [8, 256] {
+        let transcript = transcript(max_depth);
+        let fs = replay(&transcript);
+        assert_eq!(fs.directory_sizes(), recomputed_directory_sizes(&fs));
+        group.throughput(Throughput::Elements(transcript.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("replay", max_depth),
+            &transcript,
+            |b, transcript| b.iter(|| replay(transcript)),
+        );
+        group.bench_with_input(BenchmarkId::new("cached", max_depth), &fs, |b, fs| {
+            b.iter(|| fs.directory_sizes())
+        });
+        group.bench_with_input(BenchmarkId::new("recomputed", max_depth), &fs, |b, fs| {
+            b.iter(|| recomputed_directory_sizes(fs))
+        });
+    }
+    group.finish();
+}
+
+// Resizes files all over the tree, reading the total after each change.
+fn bench_mutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resize");
+    group.sample_size(10);
+    for max_depth in [8, 256] {
+        let mut fs = replay(&transcript(max_depth));
+        let files: Vec<_> = fs
+            .walk()
+            .into_iter()
+            .filter(|&id| !fs.is_directory(id))
+            .collect();
+        let mut random = Random(max_depth as u64);
+        group.throughput(Throughput::Elements(1_000));
+        group.bench_function(BenchmarkId::from_parameter(max_depth), |b| {
+            b.iter(|| {
+                (0..1_000)
+                    .map(|_| {
+                        let file = files[random.below(files.len())];
+                        fs.set_size(file, random.below(300_000)).unwrap();
+                        fs.size(Filesystem::ROOT)
+                    })
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sizes, bench_mutation);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+use color_eyre::Result;
+use std::{fs, path::Path};
+
+use crate::filesystem::{Filesystem, FsItem};
+
+// Builds the same tree a transcript would from a real directory on disk.
+// Symbolic links are skipped so that a link back up the tree can't loop.
+// Directories still to be read are kept on a stack rather than recursed into,
+// so a deep tree on disk can't overflow the call stack.
+pub fn load(path: &Path) -> Result<Filesystem> {
+    let mut filesystem = Filesystem::new();
+    let mut pending = vec![(Filesystem::ROOT, path.to_path_buf())];
+    while let Some((directory, path)) = pending.pop() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                let id = filesystem.create(directory, &name, FsItem::directory())?;
+                pending.push((id, entry.path()));
+            } else if file_type.is_file() {
+                let size = entry.metadata()?.len() as usize;
+                filesystem.create(directory, &name, FsItem::File(size))?;
+            }
+        }
+    }
+    Ok(filesystem)
+}
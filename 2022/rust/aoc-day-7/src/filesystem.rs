@@ -0,0 +1,215 @@
+use color_eyre::{eyre::eyre, Result};
+use std::collections::BTreeMap;
+
+pub type NodeId = usize;
+
+#[derive(Debug)]
+pub enum FsItem {
+    Directory(BTreeMap<String, NodeId>),
+    File(usize),
+}
+
+impl FsItem {
+    pub fn directory() -> Self {
+        FsItem::Directory(BTreeMap::new())
+    }
+}
+
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub item: FsItem,
+    // A file's own size, or for a directory the total of everything under it.
+    // Kept up to date by the filesystem as nodes are added, removed or resized.
+    pub size: usize,
+}
+
+// All nodes live in one arena and refer to each other by index, so moving to a
+// parent or child is a lookup rather than a walk from the root. The root
+// directory is always node 0.
+#[derive(Debug)]
+pub struct Filesystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filesystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        Filesystem {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                item: FsItem::directory(),
+                size: 0,
+            }],
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[id].item {
+            FsItem::Directory(contents) => contents.get(name).copied(),
+            FsItem::File(_) => None,
+        }
+    }
+
+    pub fn children(&self, id: NodeId) -> Vec<NodeId> {
+        match &self.nodes[id].item {
+            FsItem::Directory(contents) => contents.values().copied().collect(),
+            FsItem::File(_) => vec![],
+        }
+    }
+
+    pub fn is_directory(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].item, FsItem::Directory(_))
+    }
+
+    // Adds `item` to the directory `parent` unless something with that name
+    // is already there, and returns the id of whichever node ends up there.
+    pub fn create(&mut self, parent: NodeId, name: &str, item: FsItem) -> Result<NodeId> {
+        let id = self.nodes.len();
+        match &mut self.nodes[parent].item {
+            FsItem::File(_) => Err(eyre!("Can't add item to file")),
+            FsItem::Directory(contents) => {
+                if let Some(&existing) = contents.get(name) {
+                    return Ok(existing);
+                }
+                contents.insert(name.to_string(), id);
+                let size = match item {
+                    FsItem::File(size) => size,
+                    FsItem::Directory(_) => 0,
+                };
+                self.nodes.push(Node {
+                    name: name.to_string(),
+                    parent: Some(parent),
+                    item,
+                    size,
+                });
+                self.grow(parent, size);
+                Ok(id)
+            }
+        }
+    }
+
+    // Detaches `id` (and everything under it) from its parent. The nodes stay in
+    // the arena but are no longer reachable from the root.
+    pub fn remove(&mut self, id: NodeId) -> Result<()> {
+        let parent = self.nodes[id]
+            .parent
+            .ok_or_else(|| eyre!("Can't remove the root directory"))?;
+        let name = self.nodes[id].name.clone();
+        if let FsItem::Directory(contents) = &mut self.nodes[parent].item {
+            contents.remove(&name);
+        }
+        self.nodes[id].parent = None;
+        self.shrink(parent, self.nodes[id].size);
+        Ok(())
+    }
+
+    pub fn set_size(&mut self, id: NodeId, size: usize) -> Result<()> {
+        match &mut self.nodes[id].item {
+            FsItem::File(current) => *current = size,
+            FsItem::Directory(_) => return Err(eyre!("{} is a directory", self.path(id))),
+        }
+        let previous = self.nodes[id].size;
+        self.nodes[id].size = size;
+        if let Some(parent) = self.nodes[id].parent {
+            self.shrink(parent, previous);
+            self.grow(parent, size);
+        }
+        Ok(())
+    }
+
+    // Adds `size` to the directory `id` and every directory above it.
+    fn grow(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size += size;
+            current = self.nodes[id].parent;
+        }
+    }
+
+    fn shrink(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size -= size;
+            current = self.nodes[id].parent;
+        }
+    }
+
+    pub fn is_ancestor(&self, ancestor: NodeId, id: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes[id].parent;
+        }
+        false
+    }
+
+    // Every node reachable from the root, each directory before its contents.
+    pub fn walk(&self) -> Vec<NodeId> {
+        self.walk_from(Self::ROOT)
+    }
+
+    pub fn walk_from(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = vec![];
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            order.push(id);
+            pending.extend(self.children(id).into_iter().rev());
+        }
+        order
+    }
+
+    pub fn directories(&self) -> Vec<NodeId> {
+        self.walk()
+            .into_iter()
+            .filter(|&id| self.is_directory(id))
+            .collect()
+    }
+
+    // Size of every node, indexed by id. Nodes that have been removed keep the
+    // size they had when they were detached.
+    pub fn sizes(&self) -> Vec<usize> {
+        self.nodes.iter().map(|node| node.size).collect()
+    }
+
+    pub fn directory_sizes(&self) -> Vec<usize> {
+        self.directories()
+            .into_iter()
+            .map(|id| self.nodes[id].size)
+            .collect()
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        self.nodes[id].size
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+}
@@ -0,0 +1,9 @@
+pub mod disk;
+pub mod filesystem;
+pub mod planner;
+pub mod query;
+pub mod render;
+pub mod shell;
+
+pub use filesystem::{Filesystem, FsItem, Node, NodeId};
+pub use shell::Shell;
@@ -1,205 +1,329 @@
+use aoc_day_7::{disk, planner, query, render, Filesystem, Shell};
 use color_eyre::{eyre::eyre, Result};
 use std::{
-    cell::RefCell,
-    collections::HashMap,
+    env,
     fs::File,
-    io::{BufRead, BufReader},
+    io::{self, BufRead, BufReader},
     path::Path,
-    rc::Rc,
-    vec,
 };
 
-fn main() -> Result<()> {
-    color_eyre::install().unwrap();
-    part2()?;
-    Ok(())
-}
+const USAGE: &str = "\
+Usage: aoc-day-7 [options] [command [args]]
 
-fn part1() -> Result<()> {
-    let path = Path::new("commands.txt");
-    let file = File::open(path)?;
-    let mut shell = Shell::new();
-    BufReader::new(file)
-        .lines()
-        .for_each(|command| shell.execute_command(&command.unwrap()).unwrap());
-    println!("{:?}", shell);
-    println!(
-        "{:?}",
-        shell
-            .directory_sizes()
-            .into_iter()
-            .filter(|&x| x <= 100000)
-            .sum::<usize>()
-    );
-
-    Ok(())
-}
+Options:
+  -i, --input PATH     transcript to replay, `-` for stdin (default commands.txt)
+  --dir PATH           load a real directory instead of a transcript
+  --part 1|2           only print the answer to one part
+  --threshold SIZE     largest directory counted by part 1 (default 100000)
+  --disk-size SIZE     total disk size for part 2 and plan (default 70000000)
+  --required SIZE      free space needed for part 2 and plan (default 30000000)
+  --strict             report inconsistencies in the transcript
+  --files              let plan delete individual files as well
 
-fn part2() -> Result<()> {
-    let path = Path::new("commdands.txt");
-    let file = File::open(path)?;
-    let mut shell = Shell::new();
-    BufReader::new(file)
-        .lines()
-        .for_each(|command| shell.execute_command(&command.unwrap()).unwrap());
+Commands:
+  (none)               print the puzzle answers
+  tree | du | json | transcript
+  plan                 smallest set of deletions that frees enough space
+  check                replay strictly and fail on any inconsistency
+  find [GLOB] [--min SIZE] [--max SIZE]
+  largest [N]
+  extensions
+";
 
-    let target_size = 30000000 - (70000000 - shell.size_at_path(vec![])?);
-
-    println!(
-        "{:?}",
-        shell
-            .directory_sizes()
-            .into_iter()
-            .filter(|&x| x >= target_size)
-            .min()
-    );
-
-    Ok(())
-}
-
-#[derive(Debug)]
-struct Shell {
-    cwd: Vec<String>,
-    root_dir: Rc<RefCell<FsItem>>,
-    valid_paths: Vec<Vec<String>>,
+struct Options {
+    input: String,
+    dir: Option<String>,
+    part: Option<u8>,
+    threshold: usize,
+    disk_size: usize,
+    required_free: usize,
+    strict: bool,
+    include_files: bool,
+    command: Option<String>,
+    args: Vec<String>,
 }
 
-impl Shell {
-    fn new() -> Self {
-        Shell {
-            cwd: vec![],
-            root_dir: Rc::new(RefCell::new(FsItem::Directory(Rc::new(RefCell::new(
-                HashMap::new(),
-            ))))),
-            valid_paths: vec![vec![]],
-        }
-    }
-    fn cd(&mut self, arg: &str) {
-        match arg {
-            "/" => self.cwd = vec![],
-            ".." => {
-                self.cwd.pop();
+impl Options {
+    // Options are recognised anywhere on the line; everything after the
+    // command that isn't one of them is passed on to the command.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Options {
+            input: "commands.txt".to_string(),
+            dir: None,
+            part: None,
+            threshold: 100000,
+            disk_size: 70000000,
+            required_free: 30000000,
+            strict: false,
+            include_files: false,
+            command: None,
+            args: vec![],
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| eyre!("{} requires a value\n\n{}", arg, USAGE))
+            };
+            match arg.as_str() {
+                "-i" | "--input" => options.input = value()?,
+                "--dir" => options.dir = Some(value()?),
+                "--part" => {
+                    options.part = match value()?.as_str() {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        part => return Err(eyre!("Invalid part: {}", part)),
+                    }
+                }
+                "--threshold" => options.threshold = value()?.parse()?,
+                "--disk-size" => options.disk_size = value()?.parse()?,
+                "--required" => options.required_free = value()?.parse()?,
+                "--strict" => options.strict = true,
+                "--files" => options.include_files = true,
+                "-h" | "--help" => options.command = Some("help".to_string()),
+                _ if options.command.is_none() => options.command = Some(arg),
+                _ => options.args.push(arg),
             }
-            dir => self.cwd.push(dir.to_string()),
         }
+        Ok(options)
     }
+}
 
-    fn size_at_path(&self, path: Vec<String>) -> Result<usize> {
-        self.root_dir.borrow().size_at_path(path)
+fn main() -> Result<()> {
+    color_eyre::install().unwrap();
+    let options = Options::parse(env::args().skip(1))?;
+    if options.command.as_deref() == Some("help") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    if options.command.as_deref() == Some("check") {
+        return check(&options.input);
     }
 
-    fn create(&mut self, name: &str, item: FsItem) -> Result<()> {
-        match item {
-            FsItem::Directory(_) => {
-                let mut new_path = self.cwd.clone();
-                new_path.push(name.to_string());
-                self.valid_paths.push(new_path);
+    let fs = load(&options)?;
+    match options.command.as_deref() {
+        None => {
+            if options.part != Some(2) {
+                println!("Part 1: {}", part1(&fs, options.threshold));
+            }
+            if options.part != Some(1) {
+                match smallest_deletion(&fs, options.disk_size, options.required_free) {
+                    Some(size) => println!("Part 2: {}", size),
+                    None => println!("Part 2: no directory is large enough"),
+                }
             }
-            _ => {}
         }
-        self.root_dir
-            .borrow_mut()
-            .create(self.cwd.clone(), name, item)
+        Some("tree") => print!("{}", render::tree(&fs)),
+        Some("du") => print!("{}", render::du(&fs)),
+        Some("json") => println!("{}", render::json(&fs)),
+        Some("transcript") => print!("{}", render::transcript(&fs)),
+        Some("plan") => print_plan(
+            &fs,
+            options.disk_size,
+            options.required_free,
+            options.include_files,
+        ),
+        Some(command @ ("find" | "largest" | "extensions")) => {
+            run_query(&fs, command, &options.args)?
+        }
+        Some(command) => return Err(eyre!("Unknown command: {}\n\n{}", command, USAGE)),
     }
+    Ok(())
+}
 
-    fn directory_sizes(&self) -> Vec<usize> {
-        self.valid_paths
-            .iter()
-            .map(|path| self.size_at_path(path.clone()).unwrap())
-            .collect()
+fn read_transcript(path: &str) -> Result<Box<dyn BufRead>> {
+    Ok(match path {
+        "-" => Box::new(BufReader::new(io::stdin())),
+        path => Box::new(BufReader::new(File::open(Path::new(path))?)),
+    })
+}
+
+fn load(options: &Options) -> Result<Filesystem> {
+    if let Some(dir) = &options.dir {
+        return disk::load(Path::new(dir));
     }
+    let mut shell = if options.strict {
+        Shell::strict()
+    } else {
+        Shell::new()
+    };
+    for command in read_transcript(&options.input)?.lines() {
+        shell.execute_command(&command?)?;
+    }
+    shell
+        .issues()
+        .iter()
+        .for_each(|issue| eprintln!("warning: {}", issue));
+    Ok(shell.fs)
+}
 
-    fn execute_command(&mut self, command: &str) -> Result<()> {
-        let command: Vec<&str> = command.split(' ').collect();
-        match command.len() {
-            2 => match (command.get(0).clone(), command.get(1).clone()) {
-                (Some(&"$"), Some(&"ls")) => Ok(()),
-                (Some(&"dir"), Some(name)) => Ok(self.create(
-                    name,
-                    FsItem::Directory(Rc::new(RefCell::new(HashMap::new()))),
-                )?),
-                (Some(size), Some(name)) => match size.parse::<usize>() {
-                    Ok(size) => self.create(name, FsItem::File(size)),
-                    Err(x) => Err(eyre!("Invalid size: {}", size)),
-                },
-                command => Err(eyre!("Invalid input: {:?}", command)),
-            },
-            3 => {
-                match (
-                    command.get(0).clone(),
-                    command.get(1).clone(),
-                    command.get(2).clone(),
-                ) {
-                    (Some(&"$"), Some(&"cd"), Some(name)) => Ok(self.cd(name)),
-                    _ => Err(eyre!("Invalid arguments")),
+// find [glob] [--min SIZE] [--max SIZE] | largest [N] | extensions
+fn run_query(fs: &Filesystem, command: &str, args: &[String]) -> Result<()> {
+    match command {
+        "find" => {
+            let mut pattern = None;
+            let mut min_size = 0;
+            let mut max_size = usize::MAX;
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--min" => {
+                        min_size = args
+                            .next()
+                            .ok_or_else(|| eyre!("--min requires a size"))?
+                            .parse()?
+                    }
+                    "--max" => {
+                        max_size = args
+                            .next()
+                            .ok_or_else(|| eyre!("--max requires a size"))?
+                            .parse()?
+                    }
+                    glob => pattern = Some(glob::Pattern::new(glob)?),
                 }
             }
-            _ => Err(eyre!("Too many arguments")),
+            for (id, size) in query::find(fs, pattern.as_ref(), min_size..=max_size) {
+                println!("{}\t{}", size, fs.path(id));
+            }
+        }
+        "largest" => {
+            let count = args
+                .first()
+                .map(|arg| arg.parse())
+                .transpose()?
+                .unwrap_or(10);
+            for (id, size) in query::largest(fs, count) {
+                println!("{}\t{}", size, fs.path(id));
+            }
+        }
+        _ => {
+            for (extension, size, count) in query::by_extension(fs) {
+                let extension = if extension.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    format!(".{}", extension)
+                };
+                println!("{}\t{}\t{} files", size, extension, count);
+            }
         }
     }
+    Ok(())
 }
 
-#[derive(Debug)]
-enum FsItem {
-    Directory(Rc<RefCell<HashMap<String, FsItem>>>),
-    File(usize),
+// Replays the transcript in strict mode and lists every inconsistency in it.
+fn check(path: &str) -> Result<()> {
+    let mut shell = Shell::strict();
+    for command in read_transcript(path)?.lines() {
+        shell.execute_command(&command?)?;
+    }
+    let issues = shell.issues();
+    issues.iter().for_each(|issue| println!("{}", issue));
+    if !issues.is_empty() {
+        return Err(eyre!("Transcript has {} problems", issues.len()));
+    }
+    println!("Transcript is consistent");
+    Ok(())
 }
 
-impl FsItem {
-    fn size(&self) -> usize {
-        match self {
-            FsItem::File(size) => *size,
-            FsItem::Directory(contents) => {
-                contents.borrow().iter().map(|(_, item)| item.size()).sum()
-            }
-        }
-    }
+fn part1(fs: &Filesystem, threshold: usize) -> usize {
+    fs.directory_sizes()
+        .into_iter()
+        .filter(|&x| x <= threshold)
+        .sum()
+}
+
+fn smallest_deletion(fs: &Filesystem, disk_size: usize, required_free: usize) -> Option<usize> {
+    let free = disk_size.saturating_sub(fs.size(Filesystem::ROOT));
+    let target_size = required_free.saturating_sub(free);
+    fs.directory_sizes()
+        .into_iter()
+        .filter(|&x| x >= target_size)
+        .min()
+}
 
-    pub fn size_at_path(&self, mut path: Vec<String>) -> Result<usize> {
-        if path.len() == 0 {
-            Ok(self.size())
+fn print_plan(fs: &Filesystem, disk_size: usize, required_free: usize, include_files: bool) {
+    let Some(plan) = planner::plan(fs, disk_size, required_free, include_files) else {
+        println!("Not enough space can be freed");
+        return;
+    };
+    let sizes = fs.sizes();
+    for &id in &plan.deletions {
+        let command = if fs.is_directory(id) { "rm -r" } else { "rm" };
+        println!("{} {}\t{}", command, fs.path(id), sizes[id]);
+    }
+    println!(
+        "Frees {} bytes ({} needed, {} to spare){}",
+        plan.freed,
+        plan.needed,
+        plan.freed - plan.needed,
+        if plan.optimal {
+            ""
         } else {
-            match self {
-                FsItem::File(_) => return Err(eyre!("Cannot get subpath of file")),
-                FsItem::Directory(contents) => {
-                    let next_dir = path.remove(0);
-                    contents
-                        .borrow()
-                        .get(&next_dir)
-                        .ok_or_else(|| eyre!("Directory {} does not exist", next_dir))?
-                        .size_at_path(path)
-                }
-            }
+            ", search stopped early"
         }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options> {
+        Options::parse(args.split_whitespace().map(String::from))
     }
 
-    fn add_content(&mut self, name: &str, item: FsItem) -> Result<()> {
-        match self {
-            FsItem::File(_) => Err(eyre!("Can't add item to file")),
-            FsItem::Directory(content) => {
-                if !content.borrow().contains_key(name) {
-                    content.borrow_mut().insert(name.to_string(), item);
-                };
-                Ok(())
-            }
-        }
+    #[test]
+    fn defaults_match_the_puzzle() {
+        let options = parse("").unwrap();
+        assert_eq!(options.input, "commands.txt");
+        assert_eq!(options.dir, None);
+        assert_eq!(options.part, None);
+        assert_eq!(options.threshold, 100000);
+        assert_eq!(options.disk_size, 70000000);
+        assert_eq!(options.required_free, 30000000);
+        assert!(!options.strict && !options.include_files);
+        assert_eq!(options.command, None);
+        assert!(options.args.is_empty());
     }
 
-    fn create(&mut self, mut path: Vec<String>, name: &str, item: FsItem) -> Result<()> {
-        if path.len() == 0 {
-            self.add_content(name, item)?;
-            Ok(())
-        } else {
-            let next_dir = path.remove(0);
-            match self {
-                FsItem::File(_) => return Err(eyre!("Invalid path")),
-                FsItem::Directory(contents) => contents
-                    .borrow_mut()
-                    .get_mut(&next_dir)
-                    .ok_or_else(|| eyre!("Invalid path"))?
-                    .create(path, name, item)?,
-            };
-            Ok(())
+    #[test]
+    fn options_are_recognised_anywhere() {
+        let options = parse(
+            "find -i in.txt *.txt --part 2 --threshold 5 --min 3 --disk-size 100 \
+             --required 50 --strict --files --dir d",
+        )
+        .unwrap();
+        assert_eq!(options.input, "in.txt");
+        assert_eq!(options.dir.as_deref(), Some("d"));
+        assert_eq!(options.part, Some(2));
+        assert_eq!(
+            (options.threshold, options.disk_size, options.required_free),
+            (5, 100, 50)
+        );
+        assert!(options.strict && options.include_files);
+        assert_eq!(options.command.as_deref(), Some("find"));
+        assert_eq!(options.args, vec!["*.txt", "--min", "3"]);
+        assert_eq!(parse("-h").unwrap().command.as_deref(), Some("help"));
+    }
+
+    #[test]
+    fn bad_options_are_errors() {
+        assert!(parse("--part 3").is_err());
+        assert!(parse("--threshold").is_err());
+        assert!(parse("--disk-size big").is_err());
+        assert!(parse("--input").is_err());
+    }
+
+    #[test]
+    fn answers_follow_the_limits() {
+        let mut shell = Shell::new();
+        for line in "$ cd /\n$ ls\ndir a\n100 f\n$ cd a\n$ ls\n50 g\n".lines() {
+            shell.execute_command(line).unwrap();
         }
+        assert_eq!(part1(&shell.fs, 100), 50);
+        assert_eq!(part1(&shell.fs, 150), 200);
+        assert_eq!(smallest_deletion(&shell.fs, 200, 100), Some(50));
+        assert_eq!(smallest_deletion(&shell.fs, 200, 101), Some(150));
+        assert_eq!(smallest_deletion(&shell.fs, 1000, 60), Some(50));
+        assert_eq!(smallest_deletion(&shell.fs, 150, 200), None);
     }
 }
@@ -0,0 +1,151 @@
+use crate::filesystem::{Filesystem, NodeId};
+
+// Stop searching after this many steps and report the best plan found so far.
+const SEARCH_LIMIT: usize = 100_000_000;
+
+#[derive(Debug)]
+pub struct Plan {
+    pub deletions: Vec<NodeId>,
+    pub freed: usize,
+    pub needed: usize,
+    pub optimal: bool,
+}
+
+// Finds the set of directories (and files, if `include_files` is set) whose
+// deletion frees at least the space missing on the disk while freeing as
+// little as possible. Nothing in the set is inside anything else in it.
+// Returns `None` if deleting everything still wouldn't be enough.
+pub fn plan(
+    fs: &Filesystem,
+    disk_size: usize,
+    required_free: usize,
+    include_files: bool,
+) -> Option<Plan> {
+    let sizes = fs.sizes();
+    let free = disk_size.saturating_sub(sizes[Filesystem::ROOT]);
+    let needed = required_free.saturating_sub(free);
+
+    let mut search = Search::new(fs, &sizes, include_files);
+    if needed == 0 {
+        return Some(Plan {
+            deletions: vec![],
+            freed: 0,
+            needed,
+            optimal: true,
+        });
+    }
+    if search.bound.first().copied().unwrap_or(0) < needed {
+        return None;
+    }
+    search.run(needed);
+    search.best.map(|(freed, deletions)| Plan {
+        deletions,
+        freed,
+        needed,
+        optimal: search.steps < SEARCH_LIMIT,
+    })
+}
+
+// Depth-first branch and bound over the tree flattened in pre-order, with
+// the largest entries first so good plans turn up early.
+struct Search {
+    nodes: Vec<NodeId>,
+    sizes: Vec<usize>,
+    // Index just past the subtree rooted at each position.
+    end: Vec<usize>,
+    // Most that could still be freed from each position onwards.
+    bound: Vec<usize>,
+    chosen: Vec<NodeId>,
+    best: Option<(usize, Vec<NodeId>)>,
+    steps: usize,
+}
+
+impl Search {
+    fn new(fs: &Filesystem, sizes: &[usize], include_files: bool) -> Self {
+        let mut search = Search {
+            nodes: vec![],
+            sizes: vec![],
+            end: vec![],
+            bound: vec![],
+            chosen: vec![],
+            best: None,
+            steps: 0,
+        };
+        search.flatten(fs, sizes, include_files);
+        search.bound = vec![0; search.nodes.len() + 1];
+        for index in (0..search.nodes.len()).rev() {
+            search.bound[index] = search.sizes[index] + search.bound[search.end[index]];
+        }
+        search
+    }
+
+    // Walks the tree with an explicit stack so that deep trees can't overflow
+    // the call stack. Each node is visited twice: once to add it, and once
+    // after its contents to record where its subtree ends.
+    fn flatten(&mut self, fs: &Filesystem, sizes: &[usize], include_files: bool) {
+        let by_size = |id: NodeId| {
+            let mut children = fs.children(id);
+            children.sort_by(|&a, &b| sizes[a].cmp(&sizes[b]));
+            children.into_iter().map(|child| (child, None))
+        };
+        let mut pending: Vec<(NodeId, Option<usize>)> = by_size(Filesystem::ROOT).collect();
+        while let Some((id, entered)) = pending.pop() {
+            if let Some(index) = entered {
+                self.end[index] = self.nodes.len();
+                continue;
+            }
+            if !fs.is_directory(id) && !include_files {
+                continue;
+            }
+            let index = self.nodes.len();
+            self.nodes.push(id);
+            self.sizes.push(sizes[id]);
+            self.end.push(0);
+            pending.push((id, Some(index)));
+            // Smallest first, so the largest is taken off the stack next.
+            pending.extend(by_size(id));
+        }
+    }
+
+    fn best_freed(&self) -> usize {
+        self.best.as_ref().map_or(usize::MAX, |(freed, _)| *freed)
+    }
+
+    // Tries including each node before skipping it. Including a node jumps past
+    // its subtree; the alternative of skipping it is kept on `pending` along
+    // with how much had been freed and chosen at that point.
+    fn run(&mut self, needed: usize) {
+        let mut pending = vec![(0, 0, 0)];
+        while let Some((mut index, mut freed, chosen)) = pending.pop() {
+            self.chosen.truncate(chosen);
+            loop {
+                self.steps += 1;
+                if freed >= needed {
+                    if freed < self.best_freed() {
+                        self.best = Some((freed, self.chosen.clone()));
+                    }
+                    break;
+                }
+                // Nothing beats a plan that frees exactly what is needed.
+                if index == self.nodes.len()
+                    || freed + self.bound[index] < needed
+                    || self.best_freed() == needed
+                    || freed >= self.best_freed()
+                    || self.steps >= SEARCH_LIMIT
+                {
+                    break;
+                }
+                // Deleting something empty never helps.
+                let size = self.sizes[index];
+                if size > 0 && freed + size < self.best_freed() {
+                    pending.push((index + 1, freed, self.chosen.len()));
+                    self.chosen.push(self.nodes[index]);
+                    freed += size;
+                    index = self.end[index];
+                } else {
+                    index += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use glob::{MatchOptions, Pattern};
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use crate::filesystem::{Filesystem, FsItem, NodeId};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+fn file_size(fs: &Filesystem, id: NodeId) -> Option<usize> {
+    match fs.node(id).item {
+        FsItem::File(size) => Some(size),
+        FsItem::Directory(_) => None,
+    }
+}
+
+pub fn files(fs: &Filesystem) -> Vec<(NodeId, usize)> {
+    fs.walk()
+        .into_iter()
+        .filter_map(|id| file_size(fs, id).map(|size| (id, size)))
+        .collect()
+}
+
+// Files whose size is in `sizes` and that match `pattern`. A pattern with a
+// `/` in it is matched against the whole path, otherwise just the file name.
+pub fn find(
+    fs: &Filesystem,
+    pattern: Option<&Pattern>,
+    sizes: RangeInclusive<usize>,
+) -> Vec<(NodeId, usize)> {
+    files(fs)
+        .into_iter()
+        .filter(|(_, size)| sizes.contains(size))
+        .filter(|&(id, _)| match pattern {
+            Some(pattern) if pattern.as_str().contains('/') => {
+                pattern.matches_with(&fs.path(id), MATCH_OPTIONS)
+            }
+            Some(pattern) => pattern.matches_with(&fs.node(id).name, MATCH_OPTIONS),
+            None => true,
+        })
+        .collect()
+}
+
+pub fn largest(fs: &Filesystem, count: usize) -> Vec<(NodeId, usize)> {
+    let mut files = files(fs);
+    files.sort_by(|(a, a_size), (b, b_size)| b_size.cmp(a_size).then(a.cmp(b)));
+    files.truncate(count);
+    files
+}
+
+// Total size and number of files per extension, largest total first. Names
+// without an extension (including dotfiles like `.gitignore`) are grouped
+// under an empty extension.
+pub fn by_extension(fs: &Filesystem) -> Vec<(String, usize, usize)> {
+    let mut totals: HashMap<String, (usize, usize)> = HashMap::new();
+    for (id, size) in files(fs) {
+        let extension = match fs.node(id).name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => extension.to_string(),
+            _ => String::new(),
+        };
+        let total = totals.entry(extension).or_insert((0, 0));
+        total.0 += size;
+        total.1 += 1;
+    }
+    let mut totals: Vec<(String, usize, usize)> = totals
+        .into_iter()
+        .map(|(extension, (size, count))| (extension, size, count))
+        .collect();
+    totals.sort_by(|(a, a_size, _), (b, b_size, _)| b_size.cmp(a_size).then(a.cmp(b)));
+    totals
+}
@@ -0,0 +1,172 @@
+use crate::filesystem::{Filesystem, FsItem};
+
+// Indented listing in the same format as the puzzle description:
+// - / (dir)
+//   - a (dir)
+//     - i (file, size=584)
+pub fn tree(fs: &Filesystem) -> String {
+    let mut output = String::new();
+    let mut pending = vec![(Filesystem::ROOT, 0)];
+    while let Some((id, depth)) = pending.pop() {
+        let node = fs.node(id);
+        let description = match node.item {
+            FsItem::Directory(_) => "dir".to_string(),
+            FsItem::File(size) => format!("file, size={}", size),
+        };
+        output += &format!("{}- {} ({})\n", "  ".repeat(depth), node.name, description);
+        pending.extend(
+            fs.children(id)
+                .into_iter()
+                .rev()
+                .map(|child| (child, depth + 1)),
+        );
+    }
+    output
+}
+
+// One line per directory with its total size, largest first, like
+// `du -h | sort -hr`.
+pub fn du(fs: &Filesystem) -> String {
+    let sizes = fs.sizes();
+    let mut directories = fs.directories();
+    directories.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]).then(a.cmp(&b)));
+    directories
+        .into_iter()
+        .map(|id| format!("{}\t{}\n", human_size(sizes[id]), fs.path(id)))
+        .collect()
+}
+
+// A `$ cd`/`$ ls` session that rebuilds `fs` when fed back through `Shell`.
+// Directories are entered in order with an explicit stack, where `None` marks
+// the point to go back up with `$ cd ..`.
+pub fn transcript(fs: &Filesystem) -> String {
+    let mut output = String::from("$ cd /\n");
+    let mut pending = vec![Some(Filesystem::ROOT)];
+    while let Some(entry) = pending.pop() {
+        let Some(id) = entry else {
+            output.push_str("$ cd ..\n");
+            continue;
+        };
+        if id != Filesystem::ROOT {
+            output += &format!("$ cd {}\n", quote(&fs.node(id).name));
+        }
+        output.push_str("$ ls\n");
+        let children = fs.children(id);
+        for &child in &children {
+            let node = fs.node(child);
+            match node.item {
+                FsItem::Directory(_) => output += &format!("dir {}\n", quote(&node.name)),
+                FsItem::File(size) => output += &format!("{} {}\n", size, quote(&node.name)),
+            }
+        }
+        for child in children
+            .into_iter()
+            .rev()
+            .filter(|&child| fs.is_directory(child))
+        {
+            pending.push(None);
+            pending.push(Some(child));
+        }
+    }
+    output
+}
+
+// Wraps names the shell would otherwise misread in single quotes. A single
+// quote inside the name closes the quoted part, adds the quote in double quotes
+// and opens a new quoted part, which the shell joins back into one name.
+fn quote(name: &str) -> String {
+    if name.is_empty() || name.contains([' ', '"', '\'']) {
+        format!("'{}'", name.replace('\'', "'\"'\"'"))
+    } else {
+        name.to_string()
+    }
+}
+
+// The tree as JSON, with each directory's entries under "contents". It is
+// written on one line with an explicit stack, like `tree`, so a deep tree
+// neither overflows the call stack nor grows quadratically with indentation.
+pub fn json(fs: &Filesystem) -> String {
+    let sizes = fs.sizes();
+    let mut output = String::new();
+    // A directory is visited again once its contents have been written, to
+    // close it. Every entry but the first in a directory has a comma before it.
+    let mut pending = vec![(Filesystem::ROOT, true, false)];
+    while let Some((id, first, closing)) = pending.pop() {
+        let node = fs.node(id);
+        if !closing {
+            if !first {
+                output.push(',');
+            }
+            output.push('{');
+            if fs.is_directory(id) {
+                output.push_str("\"contents\":[");
+                pending.push((id, false, true));
+                pending.extend(
+                    fs.children(id)
+                        .into_iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(index, child)| (child, index == 0, false)),
+                );
+                continue;
+            }
+        } else {
+            output.push_str("],");
+        }
+        let kind = match node.item {
+            FsItem::Directory(_) => "dir",
+            FsItem::File(_) => "file",
+        };
+        output += &format!(
+            "\"name\":{},\"size\":{},\"type\":\"{}\"}}",
+            serde_json::to_string(&node.name).unwrap(),
+            sizes[id],
+            kind
+        );
+    }
+    output
+}
+
+// Rounds up to one decimal place below 10 and to whole units above, as du does.
+// Rounding can carry a value up to 1024, which then shows in the next unit.
+fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    for (index, unit) in UNITS.iter().enumerate() {
+        value /= 1024.0;
+        let rounded = if value < 10.0 {
+            (value * 10.0).ceil() / 10.0
+        } else {
+            value.ceil()
+        };
+        if rounded < 1024.0 || index == UNITS.len() - 1 {
+            return if rounded < 10.0 {
+                format!("{:.1}{}", rounded, unit)
+            } else {
+                format!("{:.0}{}", rounded, unit)
+            };
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(1537), "1.6K");
+        assert_eq!(human_size(10239), "10K");
+        assert_eq!(human_size(102401), "101K");
+        assert_eq!(human_size(1048575), "1.0M");
+        assert_eq!(human_size(1048576), "1.0M");
+        assert_eq!(human_size(1024 * 1024 * 1024 - 1), "1.0G");
+    }
+}
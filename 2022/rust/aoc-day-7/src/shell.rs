@@ -0,0 +1,442 @@
+use color_eyre::{eyre::eyre, Result};
+use std::{collections::HashSet, fmt};
+
+use crate::filesystem::{Filesystem, FsItem, NodeId};
+
+#[derive(Debug)]
+pub struct Shell {
+    cwd: NodeId,
+    pub fs: Filesystem,
+    strict: bool,
+    line: usize,
+    listed: HashSet<NodeId>,
+    issues: Vec<Issue>,
+}
+
+// Places where a transcript contradicts itself or leaves gaps. Only collected
+// by a strict shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    ConflictingSize {
+        line: usize,
+        path: String,
+        listed: usize,
+        relisted: usize,
+    },
+    FileAndDirectory {
+        line: usize,
+        path: String,
+    },
+    UnknownDirectory {
+        line: usize,
+        path: String,
+    },
+    NeverListed {
+        path: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::ConflictingSize {
+                line,
+                path,
+                listed,
+                relisted,
+            } => write!(
+                f,
+                "line {}: {} listed with size {} but was already {}",
+                line, path, relisted, listed
+            ),
+            Issue::FileAndDirectory { line, path } => {
+                write!(f, "line {}: {} is both a file and a directory", line, path)
+            }
+            Issue::UnknownDirectory { line, path } => {
+                write!(f, "line {}: cd into {} before it was listed", line, path)
+            }
+            Issue::NeverListed { path } => {
+                write!(f, "{} was never listed, its size may be incomplete", path)
+            }
+        }
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shell {
+    pub fn new() -> Self {
+        Shell {
+            cwd: Filesystem::ROOT,
+            fs: Filesystem::new(),
+            strict: false,
+            line: 0,
+            listed: HashSet::new(),
+            issues: vec![],
+        }
+    }
+
+    // A strict shell records every inconsistency in the transcript instead of
+    // ignoring it, and carries on past `cd` into unknown directories or files so
+    // that one run reports everything.
+    pub fn strict() -> Self {
+        Shell {
+            strict: true,
+            ..Shell::new()
+        }
+    }
+
+    // Everything found so far, plus the directories that were never listed.
+    pub fn issues(&self) -> Vec<Issue> {
+        let mut issues = self.issues.clone();
+        if self.strict {
+            issues.extend(
+                self.fs
+                    .directories()
+                    .into_iter()
+                    .filter(|id| !self.listed.contains(id))
+                    .map(|id| Issue::NeverListed {
+                        path: self.fs.path(id),
+                    }),
+            );
+        }
+        issues
+    }
+
+    pub fn pwd(&self) -> String {
+        self.fs.path(self.cwd)
+    }
+
+    // Follows a `/`-separated path from the root (if absolute) or the current
+    // directory. `..` at the root stays at the root, as it does in a real shell.
+    fn resolve(&self, path: &str) -> Result<NodeId> {
+        let mut current = if path.starts_with('/') {
+            Filesystem::ROOT
+        } else {
+            self.cwd
+        };
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self.fs.parent(current).unwrap_or(Filesystem::ROOT),
+                name => self
+                    .fs
+                    .child(current, name)
+                    .ok_or_else(|| eyre!("{} does not exist in {}", name, self.fs.path(current)))?,
+            };
+        }
+        Ok(current)
+    }
+
+    // Splits a path into the directory it lives in and its final name.
+    fn resolve_parent<'a>(&self, path: &'a str) -> Result<(NodeId, &'a str)> {
+        let path = path.trim_end_matches('/');
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(("", name)) => (Filesystem::ROOT, name),
+            Some((parent, name)) => (self.resolve(parent)?, name),
+            None => (self.cwd, path),
+        };
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(eyre!("Invalid name: {}", path));
+        }
+        if !self.fs.is_directory(parent) {
+            return Err(eyre!("{} is not a directory", self.fs.path(parent)));
+        }
+        Ok((parent, name))
+    }
+
+    // Like `resolve`, but creates (and reports) any directory that doesn't exist.
+    // A path that runs into a file is reported too, and gives `None`.
+    fn resolve_or_create(&mut self, path: &str) -> Result<Option<NodeId>> {
+        let mut current = if path.starts_with('/') {
+            Filesystem::ROOT
+        } else {
+            self.cwd
+        };
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self.fs.parent(current).unwrap_or(Filesystem::ROOT),
+                name => match self.fs.child(current, name) {
+                    Some(id) => id,
+                    None => {
+                        let id = self.fs.create(current, name, FsItem::directory())?;
+                        self.issues.push(Issue::UnknownDirectory {
+                            line: self.line,
+                            path: self.fs.path(id),
+                        });
+                        id
+                    }
+                },
+            };
+            if !self.fs.is_directory(current) {
+                self.issues.push(Issue::FileAndDirectory {
+                    line: self.line,
+                    path: self.fs.path(current),
+                });
+                return Ok(None);
+            }
+        }
+        Ok(Some(current))
+    }
+
+    fn cd(&mut self, path: &str) -> Result<()> {
+        let target = if self.strict {
+            // Stay put so the rest of the transcript can still be checked.
+            match self.resolve_or_create(path)? {
+                Some(target) => target,
+                None => return Ok(()),
+            }
+        } else {
+            self.resolve(path)?
+        };
+        if !self.fs.is_directory(target) {
+            return Err(eyre!("{} is not a directory", self.fs.path(target)));
+        }
+        self.cwd = target;
+        Ok(())
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<()> {
+        let (parent, name) = self.resolve_parent(path)?;
+        if self.fs.child(parent, name).is_some() {
+            return Err(eyre!("{} already exists", path));
+        }
+        let id = self.fs.create(parent, name, FsItem::directory())?;
+        self.listed.insert(id);
+        Ok(())
+    }
+
+    fn touch(&mut self, path: &str, size: usize) -> Result<()> {
+        let (parent, name) = self.resolve_parent(path)?;
+        match self.fs.child(parent, name) {
+            Some(id) => self.fs.set_size(id, size),
+            None => {
+                self.fs.create(parent, name, FsItem::File(size))?;
+                Ok(())
+            }
+        }
+    }
+
+    // Only a recursive `rm` removes directories, as in a real shell.
+    fn rm(&mut self, path: &str, recursive: bool) -> Result<()> {
+        let target = self.resolve(path)?;
+        if !recursive && self.fs.is_directory(target) {
+            return Err(eyre!("{} is a directory", self.fs.path(target)));
+        }
+        if self.fs.is_ancestor(target, self.cwd) {
+            return Err(eyre!(
+                "Can't remove {}, it contains the current directory",
+                self.fs.path(target)
+            ));
+        }
+        self.fs.remove(target)
+    }
+
+    fn create(&mut self, name: &str, item: FsItem) -> Result<()> {
+        if self.strict {
+            if let Some(existing) = self.fs.child(self.cwd, name) {
+                let path = self.fs.path(existing);
+                match (&self.fs.node(existing).item, &item) {
+                    (FsItem::File(listed), FsItem::File(relisted)) if listed != relisted => {
+                        self.issues.push(Issue::ConflictingSize {
+                            line: self.line,
+                            path,
+                            listed: *listed,
+                            relisted: *relisted,
+                        })
+                    }
+                    (FsItem::File(_), FsItem::Directory(_))
+                    | (FsItem::Directory(_), FsItem::File(_)) => {
+                        self.issues.push(Issue::FileAndDirectory {
+                            line: self.line,
+                            path,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.fs.create(self.cwd, name, item)?;
+        Ok(())
+    }
+
+    // Commands start with `$`. Any other line is output: an `ls` entry, or the
+    // directory printed by `pwd`, which has to match where the shell is.
+    pub fn execute_command(&mut self, line: &str) -> Result<()> {
+        self.line += 1;
+        if let Some(command) = line.strip_prefix('$') {
+            let args = tokenize(command)?;
+            let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+            return match args.as_slice() {
+                ["ls"] => {
+                    self.listed.insert(self.cwd);
+                    Ok(())
+                }
+                ["pwd"] => Ok(()),
+                ["cd", path] => self.cd(path),
+                ["mkdir", path] => self.mkdir(path),
+                ["touch", size, path] => match size.parse::<usize>() {
+                    Ok(size) => self.touch(path, size),
+                    Err(_) => Err(eyre!("Invalid size: {}", size)),
+                },
+                ["rm", path] => self.rm(path, false),
+                ["rm", "-r" | "-rf", path] => self.rm(path, true),
+                _ => Err(eyre!("Invalid command: {}", line)),
+            };
+        }
+        if line.starts_with('/') {
+            let pwd = self.pwd();
+            if line != pwd {
+                return Err(eyre!("pwd printed {} but the shell is in {}", line, pwd));
+            }
+            return Ok(());
+        }
+        match line.split_once(' ') {
+            Some(("dir", name)) => self.create(&unquote(name), FsItem::directory()),
+            Some((size, name)) => match size.parse::<usize>() {
+                Ok(size) => self.create(&unquote(name), FsItem::File(size)),
+                Err(_) => Err(eyre!("Invalid size: {}", size)),
+            },
+            None => Err(eyre!("Invalid input: {}", line)),
+        }
+    }
+}
+
+// Splits a command line on spaces, keeping anything inside single or double
+// quotes together.
+fn tokenize(command: &str) -> Result<Vec<String>> {
+    let mut tokens = vec![];
+    let mut token: Option<String> = None;
+    let mut quote = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => token.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                token.get_or_insert_with(String::new);
+            }
+            (None, ' ') => tokens.extend(token.take()),
+            (None, c) => token.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(eyre!("Unterminated quote in: {}", command));
+    }
+    tokens.extend(token);
+    Ok(tokens)
+}
+
+// `ls` output can contain bare names with spaces, or a name that is quoted as a
+// whole. Anything else that merely starts with a quote is a bare name.
+fn unquote(name: &str) -> String {
+    let quoted = |c: char| c == '"' || c == '\'';
+    if name.len() >= 2 && name.starts_with(quoted) && name.ends_with(quoted) {
+        if let Ok(tokens) = tokenize(name) {
+            if let [name] = tokens.as_slice() {
+                return name.clone();
+            }
+        }
+    }
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(transcript: &str) -> Shell {
+        let mut shell = Shell::new();
+        for line in transcript.lines() {
+            shell.execute_command(line).unwrap();
+        }
+        shell
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_text_together() {
+        assert_eq!(tokenize(" cd  a ").unwrap(), vec!["cd", "a"]);
+        assert_eq!(
+            tokenize("cd \"a b\" 'c d'").unwrap(),
+            vec!["cd", "a b", "c d"]
+        );
+        assert_eq!(tokenize("cd 'a'\"'\"'b'").unwrap(), vec!["cd", "a'b"]);
+        assert_eq!(tokenize("cd ''").unwrap(), vec!["cd", ""]);
+        assert!(tokenize("cd \"a").is_err());
+    }
+
+    #[test]
+    fn unquote_only_strips_enclosing_quotes() {
+        assert_eq!(unquote("a b"), "a b");
+        assert_eq!(unquote("'a b'"), "a b");
+        assert_eq!(unquote("\"a'b\""), "a'b");
+        assert_eq!(unquote("'q"), "'q");
+        assert_eq!(unquote("\""), "\"");
+        assert_eq!(unquote("'a' 'b'"), "'a' 'b'");
+        assert_eq!(unquote("\"x\"y"), "\"x\"y");
+        let shell = shell("$ cd /\n$ ls\n20 'q\n");
+        assert!(shell.fs.child(Filesystem::ROOT, "'q").is_some());
+    }
+
+    #[test]
+    fn resolve_relative_and_absolute_paths() {
+        let mut shell = shell("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\n10 f\n$ cd b\n");
+        let a = shell.resolve("/a").unwrap();
+        let b = shell.resolve("/a/b").unwrap();
+        assert_eq!(shell.resolve("").unwrap(), b);
+        assert_eq!(shell.resolve("..").unwrap(), a);
+        assert_eq!(
+            shell.resolve("../f").unwrap(),
+            shell.resolve("/a/f").unwrap()
+        );
+        assert_eq!(shell.resolve("/../../a/./b/").unwrap(), b);
+        assert_eq!(shell.resolve("../../../..").unwrap(), Filesystem::ROOT);
+        assert!(shell.resolve("missing").is_err());
+        shell.cd("/").unwrap();
+        assert_eq!(shell.resolve("..").unwrap(), Filesystem::ROOT);
+    }
+
+    #[test]
+    fn resolve_parent_splits_off_the_name() {
+        let shell = shell("$ cd /\n$ ls\ndir a\n10 f\n$ cd a\n");
+        let a = shell.resolve("/a").unwrap();
+        assert_eq!(shell.resolve_parent("x").unwrap(), (a, "x"));
+        assert_eq!(shell.resolve_parent("/x").unwrap(), (Filesystem::ROOT, "x"));
+        assert_eq!(shell.resolve_parent("/a/x/").unwrap(), (a, "x"));
+        assert_eq!(
+            shell.resolve_parent("../x").unwrap(),
+            (Filesystem::ROOT, "x")
+        );
+        assert!(shell.resolve_parent("..").is_err());
+        assert!(shell.resolve_parent("/").is_err());
+        assert!(shell.resolve_parent("/f/x").is_err());
+        assert!(shell.resolve_parent("/missing/x").is_err());
+    }
+
+    #[test]
+    fn rm_refuses_ancestors_of_the_current_directory() {
+        let mut shell = shell("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\n$ cd b\n");
+        assert!(shell.execute_command("$ rm -r /a").is_err());
+        assert!(shell.execute_command("$ rm -r .").is_err());
+        assert!(shell.execute_command("$ rm /").is_err());
+        shell.execute_command("$ cd /").unwrap();
+        shell.execute_command("$ rm -r a").unwrap();
+        assert!(shell.resolve("/a").is_err());
+    }
+
+    #[test]
+    fn plain_rm_only_removes_files() {
+        let mut shell = shell("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n10 f\n$ cd /\n");
+        assert!(shell.execute_command("$ rm a").is_err());
+        assert!(shell.resolve("/a/f").is_ok());
+        shell.execute_command("$ rm a/f").unwrap();
+        assert!(shell.resolve("/a/f").is_err());
+        shell.execute_command("$ rm -rf a").unwrap();
+        assert!(shell.resolve("/a").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+// Helpers shared by the integration tests and benchmarks. Not every one of
+// them uses all of these.
+#![allow(dead_code)]
+
+use aoc_day_7::Shell;
+
+// The example session from the puzzle description.
+pub const PUZZLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+pub fn replay(transcript: &str) -> Shell {
+    let mut shell = Shell::new();
+    for line in transcript.lines() {
+        shell
+            .execute_command(line)
+            .unwrap_or_else(|error| panic!("{}: {}", line, error));
+    }
+    shell
+}
+
+// Small deterministic generator, so random trees are the same on every run.
+pub struct Random(pub u64);
+
+impl Random {
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as usize
+    }
+}
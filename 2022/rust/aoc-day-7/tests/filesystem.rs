@@ -0,0 +1,81 @@
+use aoc_day_7::{Filesystem, FsItem, NodeId};
+
+// /a/b/f, /a/g and /h, returning the ids of a, b, f, g and h.
+fn example() -> (Filesystem, [NodeId; 5]) {
+    let mut fs = Filesystem::new();
+    let a = fs
+        .create(Filesystem::ROOT, "a", FsItem::directory())
+        .unwrap();
+    let b = fs.create(a, "b", FsItem::directory()).unwrap();
+    let f = fs.create(b, "f", FsItem::File(1)).unwrap();
+    let g = fs.create(a, "g", FsItem::File(2)).unwrap();
+    let h = fs.create(Filesystem::ROOT, "h", FsItem::File(4)).unwrap();
+    (fs, [a, b, f, g, h])
+}
+
+#[test]
+fn nodes_link_to_parents_and_children() {
+    let (fs, [a, b, f, g, h]) = example();
+    assert_eq!(fs.parent(Filesystem::ROOT), None);
+    assert_eq!(fs.parent(f), Some(b));
+    assert_eq!(fs.parent(b), Some(a));
+    assert_eq!(fs.child(a, "g"), Some(g));
+    assert_eq!(fs.child(a, "missing"), None);
+    assert_eq!(fs.child(f, "anything"), None);
+    assert_eq!(fs.children(Filesystem::ROOT), vec![a, h]);
+    assert!(fs.children(f).is_empty());
+    assert!(fs.is_directory(b));
+    assert!(!fs.is_directory(g));
+    assert_eq!(fs.path(Filesystem::ROOT), "/");
+    assert_eq!(fs.path(f), "/a/b/f");
+}
+
+#[test]
+fn create_returns_what_is_already_there() {
+    let (mut fs, [a, _, f, g, _]) = example();
+    assert_eq!(fs.create(a, "g", FsItem::File(100)).unwrap(), g);
+    assert_eq!(fs.size(g), 2);
+    assert!(fs.create(f, "x", FsItem::File(1)).is_err());
+    assert_eq!(fs.size(Filesystem::ROOT), 7);
+}
+
+#[test]
+fn walks_visit_directories_before_their_contents() {
+    let (fs, [a, b, f, g, h]) = example();
+    assert_eq!(fs.walk(), vec![Filesystem::ROOT, a, b, f, g, h]);
+    assert_eq!(fs.walk_from(a), vec![a, b, f, g]);
+    assert_eq!(fs.directories(), vec![Filesystem::ROOT, a, b]);
+    assert_eq!(fs.directory_sizes(), vec![7, 3, 1]);
+}
+
+#[test]
+fn ancestors_include_the_node_itself() {
+    let (fs, [a, b, f, _, h]) = example();
+    assert!(fs.is_ancestor(Filesystem::ROOT, f));
+    assert!(fs.is_ancestor(a, f));
+    assert!(fs.is_ancestor(f, f));
+    assert!(!fs.is_ancestor(f, a));
+    assert!(!fs.is_ancestor(b, h));
+}
+
+#[test]
+fn remove_detaches_a_subtree() {
+    let (mut fs, [a, b, f, g, h]) = example();
+    assert!(fs.remove(Filesystem::ROOT).is_err());
+    fs.remove(b).unwrap();
+    assert_eq!(fs.child(a, "b"), None);
+    assert_eq!(fs.parent(b), None);
+    assert_eq!(fs.walk(), vec![Filesystem::ROOT, a, g, h]);
+    assert_eq!(fs.size(a), 2);
+    // The removed nodes are still there, just unreachable.
+    assert_eq!(fs.parent(f), Some(b));
+    assert_eq!(fs.size(b), 1);
+}
+
+#[test]
+fn only_files_can_be_resized() {
+    let (mut fs, [a, _, f, _, _]) = example();
+    assert!(fs.set_size(a, 10).is_err());
+    fs.set_size(f, 10).unwrap();
+    assert_eq!(fs.sizes()[..3], [16, 12, 10]);
+}
@@ -0,0 +1,102 @@
+use aoc_day_7::{shell::Issue, Shell};
+
+fn replay(transcript: &str) -> (Shell, Vec<Issue>) {
+    let mut shell = Shell::strict();
+    for line in transcript.lines() {
+        shell.execute_command(line).unwrap();
+    }
+    let issues = shell.issues();
+    (shell, issues)
+}
+
+#[test]
+fn consistent_transcripts_have_no_issues() {
+    let (_, issues) = replay("$ cd /\n$ ls\ndir a\n10 f\n$ cd a\n$ ls\n20 g\n$ ls\n20 g\n");
+    assert_eq!(issues, vec![]);
+}
+
+#[test]
+fn conflicting_sizes() {
+    let (_, issues) = replay("$ cd /\n$ ls\n10 a\n$ ls\n20 a\n");
+    assert_eq!(
+        issues,
+        vec![Issue::ConflictingSize {
+            line: 5,
+            path: "/a".to_string(),
+            listed: 10,
+            relisted: 20,
+        }]
+    );
+}
+
+#[test]
+fn file_and_directory_in_a_listing() {
+    let (_, issues) = replay("$ cd /\n$ ls\n10 a\ndir a\n");
+    assert_eq!(
+        issues,
+        vec![Issue::FileAndDirectory {
+            line: 4,
+            path: "/a".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn cd_into_a_file_stays_put() {
+    let (shell, issues) = replay("$ cd /\n$ ls\n10 a\n20 a\n$ cd a\n$ ls\n");
+    assert_eq!(
+        issues,
+        vec![
+            Issue::ConflictingSize {
+                line: 4,
+                path: "/a".to_string(),
+                listed: 10,
+                relisted: 20,
+            },
+            Issue::FileAndDirectory {
+                line: 5,
+                path: "/a".to_string(),
+            },
+        ]
+    );
+    assert_eq!(shell.pwd(), "/");
+}
+
+#[test]
+fn unknown_and_never_listed_directories() {
+    let (shell, issues) = replay("$ cd /\n$ ls\ndir a\n$ cd b/c\n");
+    assert_eq!(
+        issues,
+        vec![
+            Issue::UnknownDirectory {
+                line: 4,
+                path: "/b".to_string(),
+            },
+            Issue::UnknownDirectory {
+                line: 4,
+                path: "/b/c".to_string(),
+            },
+            Issue::NeverListed {
+                path: "/a".to_string(),
+            },
+            Issue::NeverListed {
+                path: "/b".to_string(),
+            },
+            Issue::NeverListed {
+                path: "/b/c".to_string(),
+            },
+        ]
+    );
+    assert_eq!(shell.pwd(), "/b/c");
+}
+
+#[test]
+fn lenient_shells_collect_nothing() {
+    let mut shell = Shell::new();
+    for line in ["$ cd /", "$ ls", "10 a", "20 a", "dir b"] {
+        shell.execute_command(line).unwrap();
+    }
+    assert!(shell.execute_command("$ cd a").is_err());
+    assert!(shell.execute_command("$ cd c").is_err());
+    assert_eq!(shell.issues(), vec![]);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use aoc_day_7::{planner, Filesystem, FsItem, NodeId};
+use common::Random;
+
+fn random_tree(rng: &mut Random) -> Filesystem {
+    let mut fs = Filesystem::new();
+    let mut directories = vec![Filesystem::ROOT];
+    for index in 0..rng.below(12) + 1 {
+        let parent = directories[rng.below(directories.len())];
+        let name = index.to_string();
+        if rng.below(3) == 0 {
+            directories.push(fs.create(parent, &name, FsItem::directory()).unwrap());
+        } else {
+            fs.create(parent, &name, FsItem::File(rng.below(100) + 1))
+                .unwrap();
+        }
+    }
+    fs
+}
+
+// Least that can be freed by deleting a set of nodes where none is inside
+// another, trying every subset.
+fn brute_force(fs: &Filesystem, needed: usize, include_files: bool) -> Option<usize> {
+    let candidates: Vec<NodeId> = fs
+        .walk()
+        .into_iter()
+        .filter(|&id| id != Filesystem::ROOT && (include_files || fs.is_directory(id)))
+        .collect();
+    (0..1u32 << candidates.len())
+        .filter_map(|mask| {
+            let chosen: Vec<NodeId> = (0..candidates.len())
+                .filter(|bit| mask & (1 << bit) != 0)
+                .map(|bit| candidates[bit])
+                .collect();
+            let nested = chosen
+                .iter()
+                .any(|&a| chosen.iter().any(|&b| a != b && fs.is_ancestor(a, b)));
+            let freed: usize = chosen.iter().map(|&id| fs.size(id)).sum();
+            (!nested && freed >= needed).then_some(freed)
+        })
+        .min()
+}
+
+#[test]
+fn plans_match_a_brute_force_search() {
+    let mut rng = Random(7);
+    for _ in 0..300 {
+        let fs = random_tree(&mut rng);
+        let used = fs.size(Filesystem::ROOT);
+        let required = rng.below(used + 10) + 1;
+        for include_files in [false, true] {
+            let expected = brute_force(&fs, required, include_files);
+            let plan = planner::plan(&fs, used, required, include_files);
+            assert_eq!(plan.as_ref().map(|plan| plan.freed), expected);
+            let Some(plan) = plan else { continue };
+            assert!(plan.optimal);
+            let freed: usize = plan.deletions.iter().map(|&id| fs.size(id)).sum();
+            assert_eq!(freed, plan.freed);
+            for &a in &plan.deletions {
+                assert!(include_files || fs.is_directory(a));
+                assert!(plan
+                    .deletions
+                    .iter()
+                    .all(|&b| a == b || !fs.is_ancestor(a, b)));
+            }
+        }
+    }
+}
+
+#[test]
+fn nothing_to_delete_when_there_is_room() {
+    let mut fs = Filesystem::new();
+    fs.create(Filesystem::ROOT, "a", FsItem::File(50)).unwrap();
+    let plan = planner::plan(&fs, 100, 50, false).unwrap();
+    assert!(plan.deletions.is_empty());
+    assert_eq!((plan.freed, plan.needed), (0, 0));
+}
+
+#[test]
+fn no_plan_when_deleting_everything_is_not_enough() {
+    let mut fs = Filesystem::new();
+    let a = fs
+        .create(Filesystem::ROOT, "a", FsItem::directory())
+        .unwrap();
+    fs.create(a, "b", FsItem::File(40)).unwrap();
+    fs.create(Filesystem::ROOT, "c", FsItem::File(30)).unwrap();
+    // 70 used of 100 leaves 30 free, so 80 more is needed but only 70 exists.
+    assert!(planner::plan(&fs, 100, 110, true).is_none());
+    // Without files only the 40 in `a` can go.
+    assert!(planner::plan(&fs, 100, 80, false).is_none());
+    assert_eq!(planner::plan(&fs, 100, 70, false).unwrap().freed, 40);
+}
+
+#[test]
+fn large_trees_do_not_overflow_the_stack() {
+    let mut fs = Filesystem::new();
+    let mut parent = Filesystem::ROOT;
+    for index in 0..10_000 {
+        parent = fs
+            .create(parent, &index.to_string(), FsItem::directory())
+            .unwrap();
+        fs.create(parent, "f", FsItem::File(1)).unwrap();
+    }
+    for index in 0..200_000 {
+        let directory = fs
+            .create(
+                Filesystem::ROOT,
+                &format!("d{}", index),
+                FsItem::directory(),
+            )
+            .unwrap();
+        fs.create(directory, "f", FsItem::File(1)).unwrap();
+    }
+    let plan = planner::plan(&fs, 210_000, 150_000, false).unwrap();
+    assert_eq!(plan.freed, 150_000);
+}
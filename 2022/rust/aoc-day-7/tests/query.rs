@@ -0,0 +1,139 @@
+use aoc_day_7::{query, Filesystem, FsItem, NodeId};
+use glob::Pattern;
+
+fn example() -> Filesystem {
+    let mut fs = Filesystem::new();
+    let src = fs
+        .create(Filesystem::ROOT, "src", FsItem::directory())
+        .unwrap();
+    let nested = fs.create(src, "nested", FsItem::directory()).unwrap();
+    for (parent, name, size) in [
+        (Filesystem::ROOT, "notes.txt", 100),
+        (Filesystem::ROOT, ".gitignore", 10),
+        (Filesystem::ROOT, "Makefile", 40),
+        (src, "main.rs", 300),
+        (src, "lib.rs", 200),
+        (src, "log.txt", 50),
+        (nested, "deep.rs", 200),
+        (nested, "archive.tar.gz", 1000),
+    ] {
+        fs.create(parent, name, FsItem::File(size)).unwrap();
+    }
+    fs
+}
+
+fn paths(fs: &Filesystem, found: Vec<(NodeId, usize)>) -> Vec<String> {
+    let mut paths: Vec<String> = found.into_iter().map(|(id, _)| fs.path(id)).collect();
+    paths.sort();
+    paths
+}
+
+fn find(
+    fs: &Filesystem,
+    pattern: Option<&str>,
+    sizes: std::ops::RangeInclusive<usize>,
+) -> Vec<String> {
+    let pattern = pattern.map(|pattern| Pattern::new(pattern).unwrap());
+    paths(fs, query::find(fs, pattern.as_ref(), sizes))
+}
+
+#[test]
+fn find_matches_names_anywhere_in_the_tree() {
+    let fs = example();
+    assert_eq!(
+        find(&fs, Some("*.rs"), 0..=usize::MAX),
+        vec!["/src/lib.rs", "/src/main.rs", "/src/nested/deep.rs"]
+    );
+    assert_eq!(
+        find(&fs, Some("*.txt"), 0..=usize::MAX),
+        vec!["/notes.txt", "/src/log.txt"]
+    );
+    // Leading dots don't have to be matched literally.
+    assert_eq!(
+        find(&fs, Some("*ignore"), 0..=usize::MAX),
+        vec!["/.gitignore"]
+    );
+    assert_eq!(find(&fs, Some("src"), 0..=usize::MAX), Vec::<String>::new());
+}
+
+#[test]
+fn find_matches_whole_paths_when_the_pattern_has_a_slash() {
+    let fs = example();
+    assert_eq!(
+        find(&fs, Some("/src/*.rs"), 0..=usize::MAX),
+        vec!["/src/lib.rs", "/src/main.rs"]
+    );
+    assert_eq!(
+        find(&fs, Some("/src/**/*.rs"), 0..=usize::MAX),
+        vec!["/src/lib.rs", "/src/main.rs", "/src/nested/deep.rs"]
+    );
+    // `*` doesn't cross directories, and a relative pattern never matches.
+    assert_eq!(
+        find(&fs, Some("/*.rs"), 0..=usize::MAX),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        find(&fs, Some("src/*.rs"), 0..=usize::MAX),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn find_filters_by_size_inclusively() {
+    let fs = example();
+    assert_eq!(
+        find(&fs, None, 50..=200),
+        vec![
+            "/notes.txt",
+            "/src/lib.rs",
+            "/src/log.txt",
+            "/src/nested/deep.rs"
+        ]
+    );
+    assert_eq!(
+        find(&fs, Some("*.rs"), 201..=usize::MAX),
+        vec!["/src/main.rs"]
+    );
+    assert_eq!(find(&fs, None, 11..=39), Vec::<String>::new());
+    assert_eq!(find(&fs, None, 0..=usize::MAX).len(), 8);
+}
+
+#[test]
+fn largest_files_first() {
+    let fs = example();
+    let largest: Vec<(String, usize)> = query::largest(&fs, 4)
+        .into_iter()
+        .map(|(id, size)| (fs.path(id), size))
+        .collect();
+    assert_eq!(
+        largest,
+        vec![
+            ("/src/nested/archive.tar.gz".to_string(), 1000),
+            ("/src/main.rs".to_string(), 300),
+            ("/src/lib.rs".to_string(), 200),
+            ("/src/nested/deep.rs".to_string(), 200),
+        ]
+    );
+    assert_eq!(query::largest(&fs, 100).len(), 8);
+    assert!(query::largest(&fs, 0).is_empty());
+}
+
+#[test]
+fn totals_by_extension() {
+    let fs = example();
+    let totals = query::by_extension(&fs);
+    let expected = [
+        ("gz", 1000, 1),
+        ("rs", 700, 3),
+        ("txt", 150, 2),
+        // `.gitignore` is a dotfile with no extension, like `Makefile`.
+        ("", 50, 2),
+    ];
+    assert_eq!(
+        totals,
+        expected
+            .into_iter()
+            .map(|(extension, size, count)| (extension.to_string(), size, count))
+            .collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use aoc_day_7::{render, Filesystem, FsItem};
+use common::{replay, PUZZLE};
+use serde_json::{json, Value};
+
+#[test]
+fn tree_matches_the_puzzle_listing() {
+    // As printed in the puzzle, except that entries are sorted by name.
+    let expected = "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+    assert_eq!(render::tree(&replay(PUZZLE).fs), expected);
+}
+
+#[test]
+fn du_lists_the_largest_directories_first() {
+    assert_eq!(
+        render::du(&replay(PUZZLE).fs),
+        "47M\t/\n24M\t/d\n93K\t/a\n584\t/a/e\n"
+    );
+    // Directories of the same size keep the order they were created in.
+    let shell = replay("$ cd /\n$ ls\ndir z\ndir y\n$ cd z\n$ ls\n5 f\n$ cd ../y\n$ ls\n5 f\n");
+    assert_eq!(render::du(&shell.fs), "10\t/\n5\t/z\n5\t/y\n");
+}
+
+#[test]
+fn json_nests_contents_under_directories() {
+    let shell = replay("$ cd /\n$ ls\ndir a\n3 a\"b\n$ cd a\n$ ls\ndir e\n4 f\n");
+    let json: Value = serde_json::from_str(&render::json(&shell.fs)).unwrap();
+    let expected = json!({
+        "name": "/",
+        "type": "dir",
+        "size": 7,
+        "contents": [
+            {
+                "name": "a",
+                "type": "dir",
+                "size": 4,
+                "contents": [
+                    { "name": "e", "type": "dir", "size": 0, "contents": [] },
+                    { "name": "f", "type": "file", "size": 4 },
+                ],
+            },
+            { "name": "a\"b", "type": "file", "size": 3 },
+        ],
+    });
+    assert_eq!(json, expected);
+}
+
+#[test]
+fn json_handles_deep_trees() {
+    let mut fs = Filesystem::new();
+    let mut parent = Filesystem::ROOT;
+    for _ in 0..5_000 {
+        parent = fs.create(parent, "d", FsItem::directory()).unwrap();
+    }
+    fs.create(parent, "f", FsItem::File(1)).unwrap();
+    let json = render::json(&fs);
+    assert_eq!(json.matches("\"contents\":[").count(), 5_001);
+    assert!(json.ends_with("\"name\":\"/\",\"size\":1,\"type\":\"dir\"}"));
+}
@@ -0,0 +1,82 @@
+use aoc_day_7::{Filesystem, FsItem, Shell};
+
+// Checks every cached directory size against a fresh count of the files
+// under it.
+fn assert_sizes_consistent(fs: &Filesystem) {
+    for id in fs.directories() {
+        let total: usize = fs
+            .walk_from(id)
+            .into_iter()
+            .filter_map(|id| match fs.node(id).item {
+                FsItem::File(size) => Some(size),
+                FsItem::Directory(_) => None,
+            })
+            .sum();
+        assert_eq!(fs.size(id), total, "size of {}", fs.path(id));
+    }
+}
+
+fn replay(transcript: &str) -> Shell {
+    let mut shell = Shell::new();
+    for line in transcript.lines() {
+        shell.execute_command(line).unwrap();
+    }
+    assert_sizes_consistent(&shell.fs);
+    shell
+}
+
+const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+100 f
+$ cd a
+$ ls
+dir b
+20 g
+$ cd b
+$ ls
+3 h
+";
+
+#[test]
+fn touch_resizes_a_file() {
+    let mut shell = replay(EXAMPLE);
+    assert_eq!(shell.fs.size(Filesystem::ROOT), 123);
+    for command in ["$ touch 50 h", "$ touch 1 /a/g", "$ touch 7 /a/b/new"] {
+        shell.execute_command(command).unwrap();
+        assert_sizes_consistent(&shell.fs);
+    }
+    assert_eq!(shell.fs.size(Filesystem::ROOT), 158);
+}
+
+#[test]
+fn rm_subtracts_a_whole_subtree() {
+    let mut shell = replay(EXAMPLE);
+    shell.execute_command("$ cd /").unwrap();
+    shell.execute_command("$ rm -r a/b").unwrap();
+    assert_sizes_consistent(&shell.fs);
+    assert_eq!(shell.fs.size(Filesystem::ROOT), 120);
+    shell.execute_command("$ rm -r a").unwrap();
+    assert_sizes_consistent(&shell.fs);
+    assert_eq!(shell.fs.size(Filesystem::ROOT), 100);
+}
+
+#[test]
+fn resizing_inside_a_removed_subtree_leaves_the_tree_alone() {
+    let mut fs = Filesystem::new();
+    let a = fs
+        .create(Filesystem::ROOT, "a", FsItem::directory())
+        .unwrap();
+    let b = fs.create(a, "b", FsItem::directory()).unwrap();
+    let file = fs.create(b, "f", FsItem::File(10)).unwrap();
+    fs.create(Filesystem::ROOT, "g", FsItem::File(5)).unwrap();
+    fs.remove(a).unwrap();
+    assert_sizes_consistent(&fs);
+    assert_eq!(fs.size(Filesystem::ROOT), 5);
+    fs.set_size(file, 1000).unwrap();
+    assert_sizes_consistent(&fs);
+    assert_eq!(fs.size(Filesystem::ROOT), 5);
+    // The detached subtree still adds up on its own.
+    assert_eq!((fs.size(a), fs.size(b)), (1000, 1000));
+}
@@ -0,0 +1,69 @@
+use aoc_day_7::{disk, render, Filesystem, FsItem, Shell};
+use std::{env, fs, path::PathBuf, process};
+
+// A scratch directory under the system temp dir, removed again on drop.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("aoc-day-7-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Scratch(path)
+    }
+
+    fn file(&self, path: &str, size: usize) {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; size]).unwrap();
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn transcript_rebuilds_a_directory_from_disk() {
+    let scratch = Scratch::new("round-trip");
+    scratch.file("plain/file.txt", 12);
+    scratch.file("with space/inner dir/a b", 3);
+    scratch.file("a\"b/'q", 7);
+    scratch.file("it's/\"quoted\"", 5);
+    scratch.file("'a' 'b'/x", 1);
+    scratch.file("\"x\"y", 9);
+    scratch.file("top", 100);
+    fs::create_dir_all(scratch.0.join("empty")).unwrap();
+
+    let loaded = disk::load(&scratch.0).unwrap();
+    let transcript = render::transcript(&loaded);
+    let mut shell = Shell::strict();
+    for line in transcript.lines() {
+        shell
+            .execute_command(line)
+            .unwrap_or_else(|error| panic!("{}: {}", line, error));
+    }
+    assert_eq!(shell.issues(), vec![]);
+    assert_eq!(render::tree(&shell.fs), render::tree(&loaded));
+    assert_eq!(shell.fs.sizes()[0], 137);
+}
+
+#[test]
+fn transcript_handles_deep_trees() {
+    let mut fs = Filesystem::new();
+    let mut parent = Filesystem::ROOT;
+    for _ in 0..5_000 {
+        fs.create(parent, "f", FsItem::File(1)).unwrap();
+        parent = fs.create(parent, "d", FsItem::directory()).unwrap();
+    }
+    let transcript = render::transcript(&fs);
+    assert_eq!(transcript.matches("$ cd ..").count(), 5_000);
+    let mut shell = Shell::new();
+    for line in transcript.lines() {
+        shell.execute_command(line).unwrap();
+    }
+    assert_eq!(shell.fs.size(Filesystem::ROOT), 5_000);
+    assert_eq!(shell.pwd(), "/");
+}
@@ -0,0 +1,30 @@
+use crate::grid::TreeGrid;
+use color_eyre::{eyre::eyre, Result};
+
+// A `width` × `height` grid of random tree heights from 0 to 9. The same seed
+// always grows the same forest.
+pub fn generate(width: usize, height: usize, seed: u64) -> Result<TreeGrid> {
+    let trees = width
+        .checked_mul(height)
+        .ok_or_else(|| eyre!("A {} × {} forest is too big", width, height))?;
+    let mut state = seed ^ 0x2545f4914f6cdd1d;
+    let heights = (0..trees)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % 10) as u8
+        })
+        .collect();
+    TreeGrid::new(width, heights)
+}
+
+// The grid in the puzzle's input format, one row of digits per line.
+pub fn to_text(grid: &TreeGrid) -> String {
+    let mut text = String::with_capacity(grid.len() + grid.height());
+    for row in grid.heights().chunks(grid.width()) {
+        text.extend(row.iter().map(|&height| (b'0' + height) as char));
+        text.push('\n');
+    }
+    text
+}
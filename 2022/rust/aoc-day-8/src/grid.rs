@@ -0,0 +1,252 @@
+use color_eyre::{eyre::eyre, Result};
+use std::ops::Index;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, column: usize) -> Self {
+        Coord { row, column }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    // The directions the puzzle looks in, along rows and columns.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    // Change in (row, column) for one step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+}
+
+// Tree heights stored row by row. Every row has the same width, and the grid
+// always has at least one tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeGrid {
+    width: usize,
+    height: usize,
+    heights: Vec<u8>,
+}
+
+impl TreeGrid {
+    pub fn new(width: usize, heights: Vec<u8>) -> Result<Self> {
+        if width == 0 || heights.is_empty() {
+            return Err(eyre!("A tree grid needs at least one tree"));
+        }
+        if !heights.len().is_multiple_of(width) {
+            return Err(eyre!(
+                "{} trees don't fill rows of width {}",
+                heights.len(),
+                width
+            ));
+        }
+        Ok(TreeGrid {
+            width,
+            height: heights.len() / width,
+            heights,
+        })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<u8>>) -> Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(index) = rows.iter().position(|row| row.len() != width) {
+            return Err(eyre!(
+                "Row {} has {} trees but row 1 has {}",
+                index + 1,
+                rows[index].len(),
+                width
+            ));
+        }
+        TreeGrid::new(width, rows.into_iter().flatten().collect())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heights.is_empty()
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.column < self.width
+    }
+
+    // Position of `coord` in row-major order, which is also how per-tree
+    // results are laid out.
+    pub fn index(&self, coord: Coord) -> usize {
+        coord.row * self.width + coord.column
+    }
+
+    pub fn coord(&self, index: usize) -> Coord {
+        Coord::new(index / self.width, index % self.width)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<u8> {
+        self.contains(coord).then(|| self[coord])
+    }
+
+    pub fn heights(&self) -> &[u8] {
+        &self.heights
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.len()).map(|index| self.coord(index))
+    }
+
+    // The neighbour of `coord` in `direction`, if it's still on the grid.
+    pub fn step(&self, coord: Coord, direction: Direction) -> Option<Coord> {
+        let (rows, columns) = direction.delta();
+        let next = Coord::new(
+            coord.row.checked_add_signed(rows)?,
+            coord.column.checked_add_signed(columns)?,
+        );
+        self.contains(next).then_some(next)
+    }
+
+    // Every tree from `start` to the edge of the grid in `direction`,
+    // including `start` itself.
+    pub fn walk(&self, start: Coord, direction: Direction) -> Walk<'_> {
+        Walk {
+            grid: self,
+            next: self.contains(start).then_some(start),
+            direction,
+        }
+    }
+
+    // The trees seen looking out from `from` in `direction`, nearest first.
+    pub fn ray(&self, from: Coord, direction: Direction) -> Walk<'_> {
+        let mut walk = self.walk(from, direction);
+        walk.next();
+        walk
+    }
+
+    pub fn row(&self, row: usize) -> Walk<'_> {
+        self.walk(Coord::new(row, 0), Direction::Right)
+    }
+
+    pub fn column(&self, column: usize) -> Walk<'_> {
+        self.walk(Coord::new(0, column), Direction::Down)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Walk<'_>> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Walk<'_>> {
+        (0..self.width).map(|column| self.column(column))
+    }
+
+    // Every line through the grid in `direction`, each starting from the tree
+    // on the edge it points away from. For the orthogonal directions these are
+    // the rows or columns in order.
+    pub fn lines(&self, direction: Direction) -> impl Iterator<Item = Walk<'_>> {
+        self.edge()
+            .filter(move |&coord| self.step(coord, direction.opposite()).is_none())
+            .map(move |start| self.walk(start, direction))
+    }
+
+    // The trees around the outside of the grid in row-major order.
+    fn edge(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height).flat_map(move |row| {
+            // Rows in the middle only touch the edge at their first and last
+            // trees.
+            let step = if row == 0 || row == self.height - 1 {
+                1
+            } else {
+                (self.width - 1).max(1)
+            };
+            (0..self.width)
+                .step_by(step)
+                .map(move |column| Coord::new(row, column))
+        })
+    }
+}
+
+impl Index<Coord> for TreeGrid {
+    type Output = u8;
+
+    fn index(&self, coord: Coord) -> &u8 {
+        assert!(self.contains(coord), "{:?} is outside the grid", coord);
+        &self.heights[coord.row * self.width + coord.column]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Walk<'a> {
+    grid: &'a TreeGrid,
+    next: Option<Coord>,
+    direction: Direction,
+}
+
+impl Iterator for Walk<'_> {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Coord> {
+        let current = self.next?;
+        self.next = self.grid.step(current, self.direction);
+        Some(current)
+    }
+}
@@ -0,0 +1,17 @@
+pub mod forest;
+mod grid;
+mod loader;
+mod parallel;
+mod ranking;
+pub mod render;
+mod scenic;
+mod sight;
+mod visibility;
+
+pub use grid::{Coord, Direction, TreeGrid, Walk};
+pub use loader::{load, parse, LoadError};
+pub use parallel::{par_scenic_scores, par_visible_trees};
+pub use ranking::{best_sites, Constraints, Site};
+pub use scenic::{directional_distances, scenic_scores, viewing_distances};
+pub use sight::{line_of_sight, visible_from, LineOfSight, Viewpoint};
+pub use visibility::{mark_visible_trees, visible_trees};
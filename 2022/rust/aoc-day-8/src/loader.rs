@@ -0,0 +1,116 @@
+use crate::grid::TreeGrid;
+use std::{
+    error::Error,
+    fmt,
+    io::{self, BufRead},
+};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Empty,
+    // Lines and columns count from 1, as in an editor.
+    RaggedRow {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    NotADigit {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "Could not read the tree heights: {}", error),
+            LoadError::Empty => write!(f, "The input has no trees in it"),
+            LoadError::RaggedRow {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "Line {} has {} trees but the first row has {}",
+                line, width, expected
+            ),
+            LoadError::NotADigit {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "Line {}, column {}: {:?} is not a tree height",
+                line, column, character
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(error: io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+// Reads one row of digits per line. Blank lines before the first row or after
+// the last are ignored; between rows they count as a row with no trees.
+pub fn load(reader: impl BufRead) -> Result<TreeGrid, LoadError> {
+    let mut heights = vec![];
+    let mut width = None;
+    let mut blank_lines = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        let number = index + 1;
+        if line.is_empty() {
+            if width.is_some() {
+                blank_lines.push(number);
+            }
+            continue;
+        }
+        let expected = *width.get_or_insert(line.chars().count());
+        if let Some(&blank) = blank_lines.first() {
+            return Err(LoadError::RaggedRow {
+                line: blank,
+                width: 0,
+                expected,
+            });
+        }
+        for (column, character) in line.chars().enumerate() {
+            let height = character.to_digit(10).ok_or(LoadError::NotADigit {
+                line: number,
+                column: column + 1,
+                character,
+            })?;
+            heights.push(height as u8);
+        }
+        let row = line.chars().count();
+        if row != expected {
+            return Err(LoadError::RaggedRow {
+                line: number,
+                width: row,
+                expected,
+            });
+        }
+    }
+    match width {
+        Some(width) => Ok(TreeGrid::new(width, heights).expect("rows were checked")),
+        None => Err(LoadError::Empty),
+    }
+}
+
+pub fn parse(input: &str) -> Result<TreeGrid, LoadError> {
+    load(input.as_bytes())
+}
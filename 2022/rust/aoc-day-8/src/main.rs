@@ -1,137 +1,295 @@
-use color_eyre::eyre::eyre;
-use nalgebra::{DMatrix, Matrix2, Rotation2};
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
-    vec,
+use aoc_day_8::{
+    best_sites, forest, line_of_sight, load, par_scenic_scores, par_visible_trees, render,
+    scenic_scores, visible_trees, Constraints, Coord, TreeGrid, Viewpoint,
 };
+use color_eyre::{eyre::eyre, Result};
+use std::{env, fs, fs::File, io::BufReader, path::Path, time::Instant};
 
-fn main() -> color_eyre::Result<()> {
-    color_eyre::install()?;
-    part2()?;
-    Ok(())
+const USAGE: &str = "\
+Usage: aoc-day-8 [options] [command]
+
+Options:
+  -i, --input PATH     tree heights to read (default input.txt)
+  --parallel           compute the puzzle answers on every core
+  --seed S             seed for generated forests (default 0)
+  --plain              print maps without ANSI colours
+  --image PATH         also write the map to a PPM image
+  --scale N            pixels per tree in the image (default 4)
+  --height H           eye height of an observer off the grid (default -1)
+  --min-height H       only rank trees at least this tall
+  --min-edge D         only rank trees at least D trees in from the edge
+  --hidden             only rank trees that can't be seen from outside
+
+Commands:
+  (none)               print the puzzle answers
+  visible              map of the trees that can be seen from outside
+  heatmap              map of the scenic score of every tree
+  sight ROW COLUMN     what can be seen from a tree, or from a point off the
+                       grid, in each of the eight directions
+  best [N]             the N most scenic trees (default 10)
+  generate W H         print a random forest W trees wide and H trees high
+  compare [W H]        time serial and parallel analysis of a random forest
+                       (default 4000 × 4000)
+";
+
+struct Options {
+    input: String,
+    parallel: bool,
+    seed: u64,
+    colour: bool,
+    image: Option<String>,
+    scale: usize,
+    height: i16,
+    constraints: Constraints,
+    command: Option<String>,
+    args: Vec<String>,
 }
 
-fn init() -> color_eyre::Result<(String, (usize, usize))> {
-    let path = Path::new("input.txt");
-    let file = File::open(path)?;
-    let mut row_count = 1;
-    let input = BufReader::new(file)
-        .lines()
-        .map(|x| x.unwrap())
-        .reduce(|x, y| {
-            row_count += 1;
-            return x + &y;
-        })
-        .ok_or_else(|| eyre!("Could not read input"))?;
-    let input_length = input.len();
-    Ok((input, (row_count, input_length / row_count)))
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Options {
+            input: "input.txt".to_string(),
+            parallel: false,
+            seed: 0,
+            colour: true,
+            image: None,
+            scale: 4,
+            height: -1,
+            constraints: Constraints::default(),
+            command: None,
+            args: vec![],
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| eyre!("{} requires a value\n\n{}", arg, USAGE))
+            };
+            match arg.as_str() {
+                "-i" | "--input" => options.input = value()?,
+                "--parallel" => options.parallel = true,
+                "--seed" => options.seed = value()?.parse()?,
+                "--plain" => options.colour = false,
+                "--image" => options.image = Some(value()?),
+                "--scale" => options.scale = value()?.parse()?,
+                "--height" => options.height = value()?.parse()?,
+                "--min-height" => options.constraints.min_height = value()?.parse()?,
+                "--min-edge" => options.constraints.min_edge_distance = value()?.parse()?,
+                "--hidden" => options.constraints.hidden = true,
+                "-h" | "--help" => options.command = Some("help".to_string()),
+                _ if options.command.is_none() => options.command = Some(arg),
+                _ => options.args.push(arg),
+            }
+        }
+        Ok(options)
+    }
 }
 
-fn part2() -> color_eyre::Result<()> {
-    let (input, (row_count, column_count)) = init()?;
-    let mut trees: Vec<(usize, i16)> = input
-        .chars()
-        .map(|digit| (1, char::to_digit(digit, 10).unwrap() as i16))
-        .collect();
-    let matrix = DMatrix::from_iterator(row_count, column_count, (0..trees.len()).into_iter());
-    matrix
-        .row_iter()
-        .map(|row| row.iter().cloned().collect())
-        .for_each(|index_row: Vec<usize>| {
-            calculate_score(&mut trees, &index_row).unwrap();
-        });
-    matrix
-        .column_iter()
-        .map(|column| column.iter().cloned().collect())
-        .for_each(|index_row: Vec<usize>| {
-            calculate_score(&mut trees, &index_row).unwrap();
-        });
-    println!("{}", trees.iter().map(|tree| tree.0).max().unwrap());
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let options = Options::parse(env::args().skip(1))?;
+    if options.command.as_deref() == Some("help") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    match options.command.as_deref() {
+        Some("generate") => {
+            let [width, height] = options.args.as_slice() else {
+                return Err(eyre!("generate needs a width and a height\n\n{}", USAGE));
+            };
+            let grid = forest::generate(width.parse()?, height.parse()?, options.seed)?;
+            print!("{}", forest::to_text(&grid));
+            return Ok(());
+        }
+        Some("compare") => return compare(&options),
+        _ => {}
+    }
 
+    let grid = init(&options.input)?;
+    let colours = match options.command.as_deref() {
+        None => {
+            part1(&grid, options.parallel);
+            part2(&grid, options.parallel);
+            None
+        }
+        Some("visible") => {
+            let visible = visible_trees(&grid);
+            print!(
+                "{}",
+                render::visibility_map(&grid, &visible, options.colour)
+            );
+            Some(render::visibility_colours(&visible))
+        }
+        Some("heatmap") => {
+            let scores = scenic_scores(&grid);
+            print!("{}", render::heatmap(&grid, &scores, options.colour));
+            Some(render::heatmap_colours(&scores))
+        }
+        Some("sight") => {
+            let visible = sight(&grid, &options)?;
+            print!(
+                "{}",
+                render::visibility_map(&grid, &visible, options.colour)
+            );
+            Some(render::visibility_colours(&visible))
+        }
+        Some("best") => {
+            best(&grid, &options)?;
+            None
+        }
+        Some(command) => return Err(eyre!("Unknown command: {}\n\n{}", command, USAGE)),
+    };
+    match (colours, &options.image) {
+        (Some(colours), Some(path)) => {
+            fs::write(path, render::ppm(&grid, &colours, options.scale))?;
+        }
+        (None, Some(_)) => return Err(eyre!("--image needs the visible or heatmap command")),
+        _ => {}
+    }
     Ok(())
 }
 
-fn part1() -> color_eyre::Result<()> {
-    let (input, (row_count, column_count)) = init()?;
-    let mut trees: Vec<(bool, i16)> = input
-        .chars()
-        .map(|digit| (false, char::to_digit(digit, 10).unwrap() as i16))
-        .collect();
-    let matrix = DMatrix::from_iterator(row_count, column_count, (0..trees.len()).into_iter());
-    matrix
-        .row_iter()
-        .map(|row| row.iter().cloned().collect())
-        .for_each(|mut index_row: Vec<usize>| {
-            mark_visible_trees(&mut trees, &index_row);
-            index_row.reverse();
-            mark_visible_trees(&mut trees, &index_row)
-        });
-    matrix
-        .column_iter()
-        .map(|column| column.iter().cloned().collect())
-        .for_each(|mut index_row: Vec<usize>| {
-            mark_visible_trees(&mut trees, &index_row);
-            index_row.reverse();
-            mark_visible_trees(&mut trees, &index_row)
-        });
-    println!("visible trees: {}", trees.iter().filter(|x| x.0).count());
-    Ok(())
+fn init(path: &str) -> Result<TreeGrid> {
+    let file = File::open(Path::new(path))?;
+    Ok(load(BufReader::new(file))?)
 }
 
-fn mark_visible_trees(trees: &mut Vec<(bool, i16)>, indices: &Vec<usize>) {
-    let mut max_height: i16 = -1;
-    indices.iter().for_each(|&index| {
-        let mut tree = trees.get_mut(index).unwrap();
-        if tree.1 > max_height {
-            max_height = tree.1;
-            tree.0 = true;
+// Prints the viewing distance in each direction from the point given on the
+// command line and returns which trees can be seen from it. Points on the grid
+// look from the top of that tree; anything else is an observer off the grid.
+fn sight(grid: &TreeGrid, options: &Options) -> Result<Vec<bool>> {
+    let [row, column] = options.args.as_slice() else {
+        return Err(eyre!("sight needs a row and a column\n\n{}", USAGE));
+    };
+    let (row, column): (isize, isize) = (row.parse()?, column.parse()?);
+    let viewpoint = match (usize::try_from(row), usize::try_from(column)) {
+        (Ok(r), Ok(c)) if r < grid.height() && c < grid.width() => {
+            Viewpoint::Tree(Coord::new(r, c))
         }
-    });
-}
-
-fn calculate_score(trees: &mut Vec<(usize, i16)>, indices: &Vec<usize>) -> color_eyre::Result<()> {
-    for i in 0..indices.len() {
-        if i == 0 || i == indices.len() - 1 {
-            let mut tree = trees
-                .get_mut(*indices.get(i).unwrap())
-                .ok_or_else(|| eyre!("Invalid index"))?;
-            tree.0 = 0;
-            continue;
+        _ => Viewpoint::Outside {
+            row,
+            column,
+            height: options.height,
+        },
+    };
+    let mut visible = vec![false; grid.len()];
+    for sight in line_of_sight(grid, viewpoint)? {
+        println!(
+            "{:<10} distance {:<5} sees {} trees",
+            format!("{:?}", sight.direction),
+            sight.distance,
+            sight.visible.len()
+        );
+        for coord in sight.visible {
+            visible[grid.index(coord)] = true;
         }
+    }
+    println!(
+        "Visible trees: {}",
+        visible.iter().filter(|&&visible| visible).count()
+    );
+    Ok(visible)
+}
 
-        let mut distance = 0;
-        for j in (0..i).rev() {
-            distance += 1;
-            let other_height = trees
-                .get(*indices.get(j).unwrap())
-                .ok_or_else(|| eyre!("Invalid index"))?
-                .1;
-            let mut tree = trees
-                .get_mut(*indices.get(i).unwrap())
-                .ok_or_else(|| eyre!("Invalid index"))?;
-            if other_height >= tree.1 || j == 0 {
-                tree.0 *= distance;
-                break;
-            }
-        }
+fn best(grid: &TreeGrid, options: &Options) -> Result<()> {
+    let count = match options.args.as_slice() {
+        [] => 10,
+        [count] => count.parse()?,
+        _ => return Err(eyre!("best takes at most one count\n\n{}", USAGE)),
+    };
+    let sites = best_sites(grid, &options.constraints, count);
+    if sites.is_empty() {
+        println!("No tree meets the constraints");
+    }
+    for site in sites {
+        let distances: Vec<String> = site
+            .distances
+            .iter()
+            .map(|(direction, distance)| format!("{:?} {}", direction, distance))
+            .collect();
+        println!(
+            "({}, {})\theight {}\tscore {}\t{}",
+            site.coord.row,
+            site.coord.column,
+            site.height,
+            site.score,
+            distances.join(", ")
+        );
+    }
+    Ok(())
+}
 
-        let mut distance = 0;
-        for j in i + 1..trees.len() {
-            distance += 1;
-            let other_height = trees
-                .get(*indices.get(j).unwrap())
-                .ok_or_else(|| eyre!("Invalid index"))?
-                .1;
-            let mut tree = trees
-                .get_mut(*indices.get(i).unwrap())
-                .ok_or_else(|| eyre!("Invalid index"))?;
-            if other_height >= tree.1 || j == indices.len() - 1 {
-                tree.0 *= distance;
-                break;
-            }
-        }
+// Runs both halves of the puzzle serially and then in parallel on the same
+// random forest, checking that the answers agree.
+fn compare(options: &Options) -> Result<()> {
+    let (width, height) = match options.args.as_slice() {
+        [] => (4000, 4000),
+        [width, height] => (width.parse()?, height.parse()?),
+        _ => return Err(eyre!("compare needs a width and a height\n\n{}", USAGE)),
+    };
+    let start = Instant::now();
+    let grid = forest::generate(width, height, options.seed)?;
+    println!(
+        "Generated {} × {} forest in {:.2?}",
+        width,
+        height,
+        start.elapsed()
+    );
+    println!("Using {} threads", rayon::current_num_threads());
+    let visible = timed(
+        "visible trees",
+        || visible_trees(&grid),
+        || par_visible_trees(&grid),
+    );
+    let scores = timed(
+        "scenic scores",
+        || scenic_scores(&grid),
+        || par_scenic_scores(&grid),
+    );
+    if !(visible && scores) {
+        return Err(eyre!("Serial and parallel results differ"));
     }
     Ok(())
 }
+
+// Prints how long `serial` and `parallel` take and whether they agree.
+fn timed<T: PartialEq>(
+    name: &str,
+    serial: impl FnOnce() -> T,
+    parallel: impl FnOnce() -> T,
+) -> bool {
+    let start = Instant::now();
+    let expected = serial();
+    let serial_time = start.elapsed();
+    let start = Instant::now();
+    let actual = parallel();
+    let parallel_time = start.elapsed();
+    println!(
+        "{:<14} serial {:>10.2?}  parallel {:>10.2?}  speed-up {:.1}×",
+        name,
+        serial_time,
+        parallel_time,
+        serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+    expected == actual
+}
+
+fn part1(grid: &TreeGrid, parallel: bool) {
+    let visible = if parallel {
+        par_visible_trees(grid)
+    } else {
+        visible_trees(grid)
+    };
+    println!(
+        "visible trees: {}",
+        visible.iter().filter(|&&visible| visible).count()
+    );
+}
+
+fn part2(grid: &TreeGrid, parallel: bool) {
+    let scores = if parallel {
+        par_scenic_scores(grid)
+    } else {
+        scenic_scores(grid)
+    };
+    println!("{}", scores.into_iter().max().unwrap_or(0));
+}
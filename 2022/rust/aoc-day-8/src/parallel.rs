@@ -0,0 +1,131 @@
+use crate::grid::TreeGrid;
+use rayon::prelude::*;
+
+// Columns handed to each thread when sweeping the grid a row at a time.
+const COLUMNS_PER_TASK: usize = 1024;
+
+// The trees that can still block the view of trees further along a line, as
+// (position, height) with the heights strictly decreasing. A tree hides any
+// earlier tree of the same height, so there's at most one entry per height.
+#[derive(Debug, Clone, Default)]
+struct Blockers(Vec<(usize, u8)>);
+
+impl Blockers {
+    // Viewing distance back along the line from a tree of `height` at
+    // `position`, which then becomes a blocker itself.
+    fn see(&mut self, position: usize, height: u8) -> usize {
+        while let Some(&(_, blocker)) = self.0.last() {
+            if blocker >= height {
+                break;
+            }
+            self.0.pop();
+        }
+        let distance = match self.0.last() {
+            Some(&(blocker, blocker_height)) => {
+                if blocker_height == height {
+                    self.0.pop();
+                }
+                position - blocker
+            }
+            None => position,
+        };
+        self.0.push((position, height));
+        distance
+    }
+}
+
+// Same as `visible_trees`, spread across threads. Rows are independent, so each
+// thread scans whole rows. Columns are swept a row at a time from the top and
+// then from the bottom, with every thread tracking its own block of columns, so
+// the grid is always read in memory order.
+pub fn par_visible_trees(grid: &TreeGrid) -> Vec<bool> {
+    let width = grid.width();
+    let mut visible = vec![false; grid.len()];
+    visible
+        .par_chunks_mut(width)
+        .zip(grid.heights().par_chunks(width))
+        .for_each(|(visible, heights)| {
+            mark_tallest(heights.iter().zip(visible.iter_mut()));
+            mark_tallest(heights.iter().zip(visible.iter_mut()).rev());
+        });
+    for upwards in [false, true] {
+        let mut tallest = vec![-1i16; width];
+        for position in 0..grid.height() {
+            let row = row(grid, position, upwards);
+            let heights = &grid.heights()[row * width..][..width];
+            let visible = &mut visible[row * width..][..width];
+            visible
+                .par_chunks_mut(COLUMNS_PER_TASK)
+                .zip(tallest.par_chunks_mut(COLUMNS_PER_TASK))
+                .zip(heights.par_chunks(COLUMNS_PER_TASK))
+                .for_each(|((visible, tallest), heights)| {
+                    for ((visible, tallest), &height) in
+                        visible.iter_mut().zip(tallest).zip(heights)
+                    {
+                        if height as i16 > *tallest {
+                            *tallest = height as i16;
+                            *visible = true;
+                        }
+                    }
+                });
+        }
+    }
+    visible
+}
+
+// Same as `scenic_scores`, split up the same way as `par_visible_trees`.
+pub fn par_scenic_scores(grid: &TreeGrid) -> Vec<usize> {
+    let width = grid.width();
+    let mut scores = vec![1; grid.len()];
+    scores
+        .par_chunks_mut(width)
+        .zip(grid.heights().par_chunks(width))
+        .for_each(|(scores, heights)| {
+            let mut blockers = Blockers::default();
+            for (column, (score, &height)) in scores.iter_mut().zip(heights).enumerate() {
+                *score *= blockers.see(column, height);
+            }
+            let mut blockers = Blockers::default();
+            for (column, (score, &height)) in scores.iter_mut().zip(heights).rev().enumerate() {
+                *score *= blockers.see(column, height);
+            }
+        });
+    for upwards in [false, true] {
+        let mut blockers = vec![Blockers::default(); width];
+        for position in 0..grid.height() {
+            let row = row(grid, position, upwards);
+            let heights = &grid.heights()[row * width..][..width];
+            let scores = &mut scores[row * width..][..width];
+            scores
+                .par_chunks_mut(COLUMNS_PER_TASK)
+                .zip(blockers.par_chunks_mut(COLUMNS_PER_TASK))
+                .zip(heights.par_chunks(COLUMNS_PER_TASK))
+                .for_each(|((scores, blockers), heights)| {
+                    for ((score, blockers), &height) in scores.iter_mut().zip(blockers).zip(heights)
+                    {
+                        *score *= blockers.see(position, height);
+                    }
+                });
+        }
+    }
+    scores
+}
+
+fn mark_tallest<'a>(line: impl Iterator<Item = (&'a u8, &'a mut bool)>) {
+    let mut tallest: i16 = -1;
+    for (&height, visible) in line {
+        if height as i16 > tallest {
+            tallest = height as i16;
+            *visible = true;
+        }
+    }
+}
+
+// The row `position` rows into a sweep from the top, or from the bottom.
+fn row(grid: &TreeGrid, position: usize, upwards: bool) -> usize {
+    if upwards {
+        grid.height() - 1 - position
+    } else {
+        position
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    grid::{Coord, Direction, TreeGrid},
+    scenic::directional_distances,
+    visibility::visible_trees,
+};
+
+// Conditions a tree has to meet to be considered for the treehouse. The
+// default accepts every tree.
+#[derive(Debug, Clone, Default)]
+pub struct Constraints {
+    pub min_height: u8,
+    // Fewest trees between the site and the nearest edge of the grid.
+    pub min_edge_distance: usize,
+    // Only consider trees that can't be seen from outside the grid.
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Site {
+    pub coord: Coord,
+    pub height: u8,
+    pub score: usize,
+    // Viewing distance in each orthogonal direction; their product is the
+    // score.
+    pub distances: [(Direction, usize); 4],
+}
+
+// The `count` trees with the highest scenic scores that meet `constraints`,
+// best first. Ties go to the tree that comes first in row-major order.
+pub fn best_sites(grid: &TreeGrid, constraints: &Constraints, count: usize) -> Vec<Site> {
+    let distances = Direction::ORTHOGONAL.map(|direction| directional_distances(grid, direction));
+    let visible = constraints.hidden.then(|| visible_trees(grid));
+    let mut sites: Vec<Site> = grid
+        .coords()
+        .filter(|&coord| {
+            grid[coord] >= constraints.min_height
+                && edge_distance(grid, coord) >= constraints.min_edge_distance
+                && !visible
+                    .as_ref()
+                    .is_some_and(|visible| visible[grid.index(coord)])
+        })
+        .map(|coord| {
+            let index = grid.index(coord);
+            let distances: [(Direction, usize); 4] = std::array::from_fn(|direction| {
+                (
+                    Direction::ORTHOGONAL[direction],
+                    distances[direction][index],
+                )
+            });
+            Site {
+                coord,
+                height: grid[coord],
+                score: distances.iter().map(|&(_, distance)| distance).product(),
+                distances,
+            }
+        })
+        .collect();
+    sites.sort_by(|a, b| b.score.cmp(&a.score).then(a.coord.cmp(&b.coord)));
+    sites.truncate(count);
+    sites
+}
+
+fn edge_distance(grid: &TreeGrid, coord: Coord) -> usize {
+    [
+        coord.row,
+        coord.column,
+        grid.height() - 1 - coord.row,
+        grid.width() - 1 - coord.column,
+    ]
+    .into_iter()
+    .min()
+    .unwrap()
+}
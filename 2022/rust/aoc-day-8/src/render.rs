@@ -0,0 +1,87 @@
+use crate::grid::TreeGrid;
+
+const RESET: &str = "\x1b[0m";
+// Characters for the plain-text heatmap, from lowest to highest score.
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+pub type Rgb = [u8; 3];
+
+// One character per tree: `#` for trees that can be seen from outside the grid
+// and `.` for hidden ones. With colour, every tree shows its height instead,
+// bright green when visible and dark grey when hidden.
+pub fn visibility_map(grid: &TreeGrid, visible: &[bool], colour: bool) -> String {
+    render(grid, |index| match (colour, visible[index]) {
+        (false, true) => "#".to_string(),
+        (false, false) => ".".to_string(),
+        (true, true) => format!("\x1b[1;92m{}{}", grid.heights()[index], RESET),
+        (true, false) => format!("\x1b[90m{}{}", grid.heights()[index], RESET),
+    })
+}
+
+pub fn heatmap(grid: &TreeGrid, scores: &[usize], colour: bool) -> String {
+    let max = scores.iter().copied().max().unwrap_or(0);
+    render(grid, |index| {
+        let heat = heat(scores[index], max);
+        if colour {
+            let [r, g, b] = heat_colour(heat);
+            format!("\x1b[48;2;{};{};{}m  {}", r, g, b, RESET)
+        } else {
+            let step = (heat * (RAMP.len() - 1) as f64).round() as usize;
+            (RAMP[step] as char).to_string()
+        }
+    })
+}
+
+pub fn visibility_colours(visible: &[bool]) -> Vec<Rgb> {
+    visible
+        .iter()
+        .map(|&visible| if visible { [40, 200, 60] } else { [20, 40, 20] })
+        .collect()
+}
+
+pub fn heatmap_colours(scores: &[usize]) -> Vec<Rgb> {
+    let max = scores.iter().copied().max().unwrap_or(0);
+    scores
+        .iter()
+        .map(|&score| heat_colour(heat(score, max)))
+        .collect()
+}
+
+// A binary PPM image with one `scale` × `scale` block of pixels per tree.
+pub fn ppm(grid: &TreeGrid, colours: &[Rgb], scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for y in 0..height {
+        for x in 0..width {
+            image.extend(colours[(y / scale) * grid.width() + x / scale]);
+        }
+    }
+    image
+}
+
+fn render(grid: &TreeGrid, mut cell: impl FnMut(usize) -> String) -> String {
+    let mut output = String::new();
+    for row in grid.rows() {
+        for coord in row {
+            output += &cell(grid.index(coord));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+// Scenic scores are products of four distances, so a handful of trees dwarf
+// all the rest. Scaling logarithmically keeps the ordinary trees apart.
+fn heat(score: usize, max: usize) -> f64 {
+    if max == 0 {
+        return 0.0;
+    }
+    (score as f64).ln_1p() / (max as f64).ln_1p()
+}
+
+// Black through red and yellow to white as `heat` goes from 0 to 1.
+fn heat_colour(heat: f64) -> Rgb {
+    let channel = |offset: f64| ((heat * 3.0 - offset).clamp(0.0, 1.0) * 255.0) as u8;
+    [channel(0.0), channel(1.0), channel(2.0)]
+}
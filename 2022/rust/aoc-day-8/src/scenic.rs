@@ -0,0 +1,53 @@
+use crate::grid::{Direction, TreeGrid};
+
+// How far each tree in a line can see towards the start of the line: the
+// distance to the nearest earlier tree at least as tall, or to the edge if
+// there isn't one. The stack holds the trees that could still block the view
+// of a later tree, tallest at the bottom, so every tree is pushed and popped
+// at most once.
+pub fn viewing_distances(heights: &[u8]) -> Vec<usize> {
+    let mut blocking: Vec<usize> = vec![];
+    heights
+        .iter()
+        .enumerate()
+        .map(|(index, &height)| {
+            while let Some(&top) = blocking.last() {
+                if heights[top] >= height {
+                    break;
+                }
+                blocking.pop();
+            }
+            let distance = blocking.last().map_or(index, |&top| index - top);
+            blocking.push(index);
+            distance
+        })
+        .collect()
+}
+
+// Every tree's viewing distance looking in `direction`, in row-major order.
+// Walking each line the opposite way puts the trees it looks at before it.
+pub fn directional_distances(grid: &TreeGrid, direction: Direction) -> Vec<usize> {
+    let mut distances = vec![0; grid.len()];
+    for line in grid.lines(direction.opposite()) {
+        let coords: Vec<_> = line.collect();
+        let heights: Vec<u8> = coords.iter().map(|&coord| grid[coord]).collect();
+        for (coord, distance) in coords.into_iter().zip(viewing_distances(&heights)) {
+            distances[grid.index(coord)] = distance;
+        }
+    }
+    distances
+}
+
+// Scenic score of every tree in row-major order.
+pub fn scenic_scores(grid: &TreeGrid) -> Vec<usize> {
+    let mut scores = vec![1; grid.len()];
+    for direction in Direction::ORTHOGONAL {
+        for (score, distance) in scores
+            .iter_mut()
+            .zip(directional_distances(grid, direction))
+        {
+            *score *= distance;
+        }
+    }
+    scores
+}
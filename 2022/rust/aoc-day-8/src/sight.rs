@@ -0,0 +1,142 @@
+use crate::grid::{Coord, Direction, TreeGrid};
+use color_eyre::{eyre::eyre, Result};
+
+// Where someone is looking from: the top of one of the trees, or a point off
+// the grid at a given eye height. An observer at height -1 is lying on the
+// ground, which is how the puzzle looks in from the edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Viewpoint {
+    Tree(Coord),
+    Outside {
+        row: isize,
+        column: isize,
+        height: i16,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineOfSight {
+    pub direction: Direction,
+    // Number of trees the viewer sees before one at least as tall as they are
+    // blocks the view, counting that tree. For a tree this is the puzzle's
+    // viewing distance.
+    pub distance: usize,
+    // Every tree visible in this direction, nearest first.
+    pub visible: Vec<Coord>,
+}
+
+// Looks out from `viewpoint` in all eight directions. A tree is hidden by an
+// earlier one on the same line that is at least as tall as both the viewer
+// and the tree itself, so the viewer sees everything up to the first tree as
+// tall as they are and, beyond that, only trees taller than all of those in
+// front of them.
+pub fn line_of_sight(grid: &TreeGrid, viewpoint: Viewpoint) -> Result<Vec<LineOfSight>> {
+    let (row, column, height) = match viewpoint {
+        Viewpoint::Tree(coord) => {
+            let height = grid
+                .get(coord)
+                .ok_or_else(|| eyre!("{:?} is not on the grid", coord))?;
+            (coord.row as isize, coord.column as isize, height as i16)
+        }
+        Viewpoint::Outside {
+            row,
+            column,
+            height,
+        } => {
+            if on_grid(grid, row, column).is_some() {
+                return Err(eyre!("({}, {}) is on the grid", row, column));
+            }
+            (row, column, height)
+        }
+    };
+    Ok(Direction::ALL
+        .into_iter()
+        .map(|direction| look(grid, row, column, height, direction))
+        .collect())
+}
+
+// The trees visible from `viewpoint` in any direction.
+pub fn visible_from(grid: &TreeGrid, viewpoint: Viewpoint) -> Result<Vec<Coord>> {
+    let mut visible: Vec<Coord> = line_of_sight(grid, viewpoint)?
+        .into_iter()
+        .flat_map(|sight| sight.visible)
+        .collect();
+    visible.sort();
+    Ok(visible)
+}
+
+fn look(
+    grid: &TreeGrid,
+    row: isize,
+    column: isize,
+    height: i16,
+    direction: Direction,
+) -> LineOfSight {
+    let mut sight = LineOfSight {
+        direction,
+        distance: 0,
+        visible: vec![],
+    };
+    let mut blocked = false;
+    // Tallest tree so far that is at least as tall as the viewer.
+    let mut horizon: i16 = -1;
+    for coord in ray(grid, row, column, direction) {
+        let tree = grid[coord] as i16;
+        if !blocked {
+            sight.distance += 1;
+        }
+        if !blocked || tree > horizon {
+            sight.visible.push(coord);
+        }
+        if tree >= height {
+            blocked = true;
+            horizon = horizon.max(tree);
+        }
+    }
+    sight
+}
+
+// The trees on the line from (`row`, `column`) in `direction`, not counting
+// the starting point. The start may be off the grid, in which case the line
+// can cross the grid later or miss it entirely. The steps that land on the grid
+// are worked out up front, so a start far away costs nothing to walk from.
+fn ray(
+    grid: &TreeGrid,
+    row: isize,
+    column: isize,
+    direction: Direction,
+) -> impl Iterator<Item = Coord> + '_ {
+    let (rows, columns) = direction.delta();
+    let (first, last) = match (
+        steps_inside(row, rows, grid.height()),
+        steps_inside(column, columns, grid.width()),
+    ) {
+        (Some((row_first, row_last)), Some((column_first, column_last))) => (
+            row_first.max(column_first).max(1),
+            row_last.min(column_last),
+        ),
+        _ => (1, 0),
+    };
+    (first..=last).filter_map(move |step| {
+        let row = usize::try_from(row as i128 + rows as i128 * step).ok()?;
+        let column = usize::try_from(column as i128 + columns as i128 * step).ok()?;
+        Some(Coord::new(row, column))
+    })
+}
+
+// The first and last number of steps after which `start + delta * step` is in
+// `0..size`, if there are any. Worked out in i128 so that nothing near the
+// ends of isize can overflow.
+fn steps_inside(start: isize, delta: isize, size: usize) -> Option<(i128, i128)> {
+    let (start, size) = (start as i128, size as i128);
+    match delta {
+        0 => (0..size).contains(&start).then_some((i128::MIN, i128::MAX)),
+        1 => Some((-start, size - 1 - start)),
+        _ => Some((start - (size - 1), start)),
+    }
+}
+
+fn on_grid(grid: &TreeGrid, row: isize, column: isize) -> Option<Coord> {
+    let coord = Coord::new(usize::try_from(row).ok()?, usize::try_from(column).ok()?);
+    grid.contains(coord).then_some(coord)
+}
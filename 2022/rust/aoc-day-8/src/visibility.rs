@@ -0,0 +1,29 @@
+use crate::grid::{Coord, Direction, TreeGrid};
+
+// Marks every tree on `line` that is taller than all the trees before it, and
+// so can be seen from the edge the line starts at.
+pub fn mark_visible_trees(
+    grid: &TreeGrid,
+    visible: &mut [bool],
+    line: impl Iterator<Item = Coord>,
+) {
+    let mut max_height: i16 = -1;
+    for coord in line {
+        let height = grid[coord] as i16;
+        if height > max_height {
+            max_height = height;
+            visible[grid.index(coord)] = true;
+        }
+    }
+}
+
+// Whether each tree, in row-major order, can be seen from outside the grid.
+pub fn visible_trees(grid: &TreeGrid) -> Vec<bool> {
+    let mut visible = vec![false; grid.len()];
+    for direction in Direction::ORTHOGONAL {
+        for line in grid.lines(direction) {
+            mark_visible_trees(grid, &mut visible, line);
+        }
+    }
+    visible
+}
@@ -0,0 +1,24 @@
+// Grids shared by the integration tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use aoc_day_8::TreeGrid;
+use proptest::prelude::*;
+
+// The example forest from the puzzle.
+pub fn example() -> TreeGrid {
+    TreeGrid::from_rows(
+        ["30373", "25512", "65332", "33549", "35390"]
+            .iter()
+            .map(|row| row.bytes().map(|digit| digit - b'0').collect())
+            .collect(),
+    )
+    .unwrap()
+}
+
+// Random grids with sides shorter than `max_side`.
+pub fn grid(max_side: usize) -> impl Strategy<Value = TreeGrid> {
+    (1..max_side, 1..max_side).prop_flat_map(|(height, width)| {
+        prop::collection::vec(0u8..10, width * height)
+            .prop_map(move |heights| TreeGrid::new(width, heights).unwrap())
+    })
+}
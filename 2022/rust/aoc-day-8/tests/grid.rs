@@ -0,0 +1,66 @@
+use aoc_day_8::{Coord, Direction, TreeGrid};
+
+fn example() -> TreeGrid {
+    TreeGrid::new(3, vec![1, 2, 3, 4, 5, 6]).unwrap()
+}
+
+fn heights(grid: &TreeGrid, coords: impl Iterator<Item = Coord>) -> Vec<u8> {
+    coords.map(|coord| grid[coord]).collect()
+}
+
+#[test]
+fn validates_shape() {
+    assert!(TreeGrid::new(0, vec![]).is_err());
+    assert!(TreeGrid::new(4, vec![1, 2, 3, 4, 5, 6]).is_err());
+    assert!(TreeGrid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    assert!(TreeGrid::from_rows(vec![]).is_err());
+    let grid = TreeGrid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    assert_eq!(grid, example());
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+}
+
+#[test]
+fn rows_and_columns() {
+    let grid = example();
+    let rows: Vec<_> = grid.rows().map(|row| heights(&grid, row)).collect();
+    assert_eq!(rows, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    let columns: Vec<_> = grid
+        .columns()
+        .map(|column| heights(&grid, column))
+        .collect();
+    assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+}
+
+#[test]
+fn lines_start_from_the_opposite_edge() {
+    let grid = example();
+    let lines = |direction| -> Vec<_> {
+        grid.lines(direction)
+            .map(|line| heights(&grid, line))
+            .collect()
+    };
+    assert_eq!(lines(Direction::Left), vec![vec![3, 2, 1], vec![6, 5, 4]]);
+    assert_eq!(
+        lines(Direction::Up),
+        vec![vec![4, 1], vec![5, 2], vec![6, 3]]
+    );
+    assert_eq!(
+        lines(Direction::DownRight),
+        vec![vec![1, 5], vec![2, 6], vec![3], vec![4]]
+    );
+    assert_eq!(
+        lines(Direction::UpLeft),
+        vec![vec![3], vec![4], vec![5, 1], vec![6, 2]]
+    );
+}
+
+#[test]
+fn rays_exclude_their_origin() {
+    let grid = example();
+    let from = Coord::new(1, 1);
+    assert_eq!(heights(&grid, grid.ray(from, Direction::Up)), vec![2]);
+    assert_eq!(heights(&grid, grid.ray(from, Direction::Left)), vec![4]);
+    assert_eq!(heights(&grid, grid.ray(from, Direction::Right)), vec![6]);
+    assert_eq!(heights(&grid, grid.ray(from, Direction::Down)), vec![]);
+    assert_eq!(grid.get(Coord::new(2, 0)), None);
+}
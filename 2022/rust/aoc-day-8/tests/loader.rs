@@ -0,0 +1,68 @@
+use aoc_day_8::{parse, LoadError, TreeGrid};
+use std::{error::Error, io};
+
+#[test]
+fn loads_the_example() {
+    let grid = parse("30373\n25512\n65332\n33549\n35390\n").unwrap();
+    assert_eq!((grid.width(), grid.height()), (5, 5));
+    assert_eq!(&grid.heights()[..5], &[3, 0, 3, 7, 3]);
+}
+
+#[test]
+fn ignores_carriage_returns_and_surrounding_blank_lines() {
+    let grid = parse("\n12\r\n34\r\n\n\n").unwrap();
+    assert_eq!(grid, TreeGrid::new(2, vec![1, 2, 3, 4]).unwrap());
+}
+
+#[test]
+fn reports_ragged_rows() {
+    assert!(matches!(
+        parse("123\n456\n78\n"),
+        Err(LoadError::RaggedRow {
+            line: 3,
+            width: 2,
+            expected: 3
+        })
+    ));
+    assert!(matches!(
+        parse("123\n\n456\n"),
+        Err(LoadError::RaggedRow {
+            line: 2,
+            width: 0,
+            expected: 3
+        })
+    ));
+    assert_eq!(
+        parse("12\n345").unwrap_err().to_string(),
+        "Line 2 has 3 trees but the first row has 2"
+    );
+}
+
+#[test]
+fn reports_non_digits() {
+    assert!(matches!(
+        parse("123\n4 6\n"),
+        Err(LoadError::NotADigit {
+            line: 2,
+            column: 2,
+            character: ' '
+        })
+    ));
+    assert_eq!(
+        parse("1é3").unwrap_err().to_string(),
+        "Line 1, column 2: 'é' is not a tree height"
+    );
+}
+
+#[test]
+fn reports_empty_input() {
+    assert!(matches!(parse(""), Err(LoadError::Empty)));
+    assert!(matches!(parse("\n\r\n"), Err(LoadError::Empty)));
+}
+
+#[test]
+fn io_errors_keep_their_source() {
+    let error = LoadError::from(io::Error::new(io::ErrorKind::InvalidData, "bad bytes"));
+    assert!(error.source().is_some());
+    assert!(LoadError::Empty.source().is_none());
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use aoc_day_8::{
+    forest, par_scenic_scores, par_visible_trees, scenic_scores, visible_trees, TreeGrid,
+};
+use common::grid;
+use proptest::prelude::*;
+
+#[test]
+fn generated_forests_match_serial_results() {
+    // Wider than the blocks of columns each thread sweeps, so that the last
+    // block is a partial one.
+    let grid = forest::generate(2500, 40, 7).unwrap();
+    assert_eq!(par_visible_trees(&grid), visible_trees(&grid));
+    assert_eq!(par_scenic_scores(&grid), scenic_scores(&grid));
+}
+
+#[test]
+fn generated_forests_round_trip_through_text() {
+    let grid = forest::generate(7, 3, 1).unwrap();
+    let rows = forest::to_text(&grid)
+        .lines()
+        .map(|line| line.bytes().map(|digit| digit - b'0').collect())
+        .collect();
+    assert_eq!(TreeGrid::from_rows(rows).unwrap(), grid);
+    assert_eq!(forest::generate(7, 3, 1).unwrap(), grid);
+}
+
+#[test]
+fn empty_or_oversized_forests_are_errors() {
+    assert!(forest::generate(0, 3, 0).is_err());
+    assert!(forest::generate(3, 0, 0).is_err());
+    assert!(forest::generate(usize::MAX, 2, 0).is_err());
+}
+
+proptest! {
+    #[test]
+    fn parallel_matches_serial(grid in grid(30)) {
+        prop_assert_eq!(par_visible_trees(&grid), visible_trees(&grid));
+        prop_assert_eq!(par_scenic_scores(&grid), scenic_scores(&grid));
+    }
+}
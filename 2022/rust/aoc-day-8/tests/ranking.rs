@@ -0,0 +1,94 @@
+mod common;
+
+use aoc_day_8::{best_sites, scenic_scores, visible_trees, Constraints, Coord, Direction};
+use common::{example, grid};
+use proptest::prelude::*;
+
+#[test]
+fn best_site_in_the_example() {
+    let sites = best_sites(&example(), &Constraints::default(), 2);
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].coord, Coord::new(3, 2));
+    assert_eq!(sites[0].score, 8);
+    assert_eq!(
+        sites[0].distances,
+        [
+            (Direction::Up, 2),
+            (Direction::Down, 1),
+            (Direction::Left, 2),
+            (Direction::Right, 2)
+        ]
+    );
+    assert_eq!(sites[1].coord, Coord::new(2, 1));
+}
+
+#[test]
+fn constraints_filter_sites() {
+    let grid = example();
+    let hidden = Constraints {
+        hidden: true,
+        ..Constraints::default()
+    };
+    let coords: Vec<_> = best_sites(&grid, &hidden, 10)
+        .into_iter()
+        .map(|site| site.coord)
+        .collect();
+    assert_eq!(
+        coords,
+        vec![
+            Coord::new(3, 3),
+            Coord::new(1, 3),
+            Coord::new(2, 2),
+            Coord::new(3, 1)
+        ]
+    );
+    let centre = Constraints {
+        min_edge_distance: 2,
+        ..Constraints::default()
+    };
+    let sites = best_sites(&grid, &centre, 10);
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].coord, Coord::new(2, 2));
+    let tall = Constraints {
+        min_height: 10,
+        ..Constraints::default()
+    };
+    assert!(best_sites(&grid, &tall, 10).is_empty());
+}
+
+proptest! {
+    #[test]
+    fn ranking_is_sorted_and_respects_constraints(
+        grid in grid(10),
+        min_height in 0u8..10,
+        min_edge_distance in 0usize..4,
+        hidden in any::<bool>(),
+        count in 0usize..20,
+    ) {
+        let constraints = Constraints { min_height, min_edge_distance, hidden };
+        let sites = best_sites(&grid, &constraints, count);
+        let scores = scenic_scores(&grid);
+        let visible = visible_trees(&grid);
+        let eligible = grid
+            .coords()
+            .filter(|&coord| {
+                let edge = coord
+                    .row
+                    .min(coord.column)
+                    .min(grid.height() - 1 - coord.row)
+                    .min(grid.width() - 1 - coord.column);
+                grid[coord] >= min_height
+                    && edge >= min_edge_distance
+                    && !(hidden && visible[grid.index(coord)])
+            })
+            .count();
+        prop_assert_eq!(sites.len(), count.min(eligible));
+        for pair in sites.windows(2) {
+            prop_assert!(pair[0].score >= pair[1].score);
+        }
+        for site in &sites {
+            prop_assert_eq!(site.score, scores[grid.index(site.coord)]);
+            prop_assert!(site.height >= min_height);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use aoc_day_8::{render, scenic_scores, visible_trees, TreeGrid};
+
+fn example() -> TreeGrid {
+    TreeGrid::from_rows(vec![vec![3, 0, 3], vec![2, 5, 5], vec![6, 5, 3]]).unwrap()
+}
+
+#[test]
+fn plain_visibility_map() {
+    let grid = example();
+    let visible = visible_trees(&grid);
+    assert_eq!(
+        render::visibility_map(&grid, &visible, false),
+        "###\n###\n###\n"
+    );
+}
+
+#[test]
+fn plain_heatmap_puts_the_best_tree_at_the_top_of_the_ramp() {
+    let grid = example();
+    let scores = scenic_scores(&grid);
+    assert_eq!(render::heatmap(&grid, &scores, false), "   \n @ \n   \n");
+}
+
+#[test]
+fn ppm_scales_each_tree() {
+    let grid = example();
+    let colours = render::heatmap_colours(&scenic_scores(&grid));
+    let image = render::ppm(&grid, &colours, 2);
+    let header = b"P6\n6 6\n255\n";
+    assert_eq!(&image[..header.len()], header);
+    assert_eq!(image.len(), header.len() + 6 * 6 * 3);
+    // The centre tree covers pixels (2, 2) to (3, 3) and is the only one with
+    // a non-zero score.
+    let pixel = |x: usize, y: usize| &image[header.len() + (y * 6 + x) * 3..][..3];
+    assert_eq!(pixel(2, 3), [255, 255, 255]);
+    assert_eq!(pixel(1, 1), [0, 0, 0]);
+}
@@ -0,0 +1,98 @@
+use aoc_day_8::{scenic_scores, viewing_distances, visible_trees, TreeGrid};
+use proptest::prelude::*;
+
+const EXAMPLE: [&str; 5] = ["30373", "25512", "65332", "33549", "35390"];
+
+fn brute_force_distances(heights: &[u8]) -> Vec<usize> {
+    (0..heights.len())
+        .map(|index| {
+            (0..index)
+                .rev()
+                .find(|&other| heights[other] >= heights[index])
+                .map_or(index, |other| index - other)
+        })
+        .collect()
+}
+
+// Scenic score of every tree, found by walking outwards from it in each of the
+// four directions until the view is blocked.
+fn brute_force_scores(grid: &[Vec<u8>]) -> Vec<usize> {
+    let height = grid.len();
+    let width = grid[0].len();
+    let mut scores = vec![];
+    for row in 0..height {
+        for column in 0..width {
+            let tree = grid[row][column];
+            let mut score = 1;
+            for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (mut r, mut c) = (row as isize, column as isize);
+                let mut distance = 0;
+                loop {
+                    r += dr;
+                    c += dc;
+                    if r < 0 || c < 0 || r >= height as isize || c >= width as isize {
+                        break;
+                    }
+                    distance += 1;
+                    if grid[r as usize][c as usize] >= tree {
+                        break;
+                    }
+                }
+                score *= distance;
+            }
+            scores.push(score);
+        }
+    }
+    scores
+}
+
+fn scores(grid: &[Vec<u8>]) -> Vec<usize> {
+    scenic_scores(&TreeGrid::from_rows(grid.to_vec()).unwrap())
+}
+
+fn parse(lines: &[&str]) -> Vec<Vec<u8>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|digit| digit.to_digit(10).unwrap() as u8)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn example() {
+    let grid = parse(&EXAMPLE);
+    let scores = scores(&grid);
+    assert_eq!(scores[5 + 2], 4);
+    assert_eq!(scores[3 * 5 + 2], 8);
+    assert_eq!(scores.iter().max(), Some(&8));
+    let visible = visible_trees(&TreeGrid::from_rows(grid).unwrap());
+    assert_eq!(visible.iter().filter(|&&visible| visible).count(), 21);
+}
+
+#[test]
+fn edges_see_nothing_behind_them() {
+    assert_eq!(viewing_distances(&[]), Vec::<usize>::new());
+    assert_eq!(viewing_distances(&[5]), vec![0]);
+    assert_eq!(viewing_distances(&[3, 0, 3, 7, 3]), vec![0, 1, 2, 3, 1]);
+}
+
+fn grid() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    (1usize..12, 1usize..12).prop_flat_map(|(height, width)| {
+        prop::collection::vec(prop::collection::vec(0u8..10, width), height)
+    })
+}
+
+proptest! {
+    #[test]
+    fn distances_match_brute_force(heights in prop::collection::vec(0u8..10, 0..200)) {
+        prop_assert_eq!(viewing_distances(&heights), brute_force_distances(&heights));
+    }
+
+    #[test]
+    fn scores_match_brute_force(grid in grid()) {
+        prop_assert_eq!(scores(&grid), brute_force_scores(&grid));
+    }
+}
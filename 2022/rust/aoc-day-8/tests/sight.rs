@@ -0,0 +1,154 @@
+mod common;
+
+use aoc_day_8::{
+    line_of_sight, scenic_scores, visible_from, visible_trees, Coord, Direction, TreeGrid,
+    Viewpoint,
+};
+use common::{example, grid};
+use proptest::prelude::*;
+
+fn distance(grid: &TreeGrid, viewpoint: Viewpoint, direction: Direction) -> usize {
+    line_of_sight(grid, viewpoint)
+        .unwrap()
+        .into_iter()
+        .find(|sight| sight.direction == direction)
+        .unwrap()
+        .distance
+}
+
+#[test]
+fn puzzle_viewing_distances() {
+    let grid = example();
+    let tree = Viewpoint::Tree(Coord::new(3, 2));
+    assert_eq!(distance(&grid, tree, Direction::Up), 2);
+    assert_eq!(distance(&grid, tree, Direction::Left), 2);
+    assert_eq!(distance(&grid, tree, Direction::Down), 1);
+    assert_eq!(distance(&grid, tree, Direction::Right), 2);
+}
+
+#[test]
+fn diagonals_see_over_shorter_trees() {
+    let grid = example();
+    let corner = Viewpoint::Outside {
+        row: -1,
+        column: -1,
+        height: 4,
+    };
+    let sights = line_of_sight(&grid, corner).unwrap();
+    let diagonal = sights
+        .iter()
+        .find(|sight| sight.direction == Direction::DownRight)
+        .unwrap();
+    assert_eq!(diagonal.distance, 2);
+    assert_eq!(diagonal.visible, vec![Coord::new(0, 0), Coord::new(1, 1)]);
+    assert!(sights
+        .iter()
+        .filter(|sight| sight.direction != Direction::DownRight)
+        .all(|sight| sight.visible.is_empty() && sight.distance == 0));
+}
+
+#[test]
+fn rejects_viewpoints_on_the_wrong_side_of_the_edge() {
+    let grid = example();
+    assert!(line_of_sight(&grid, Viewpoint::Tree(Coord::new(5, 0))).is_err());
+    let inside = Viewpoint::Outside {
+        row: 2,
+        column: 2,
+        height: 0,
+    };
+    assert!(line_of_sight(&grid, inside).is_err());
+}
+
+// Lines from far off the grid, even from the ends of isize, are walked
+// straight to where they cross it.
+#[test]
+fn distant_observers_see_what_nearby_ones_do() {
+    let grid = example();
+    let seen = |row, column, direction| {
+        let observer = Viewpoint::Outside {
+            row,
+            column,
+            height: -1,
+        };
+        line_of_sight(&grid, observer)
+            .unwrap()
+            .into_iter()
+            .find(|sight| sight.direction == direction)
+            .unwrap()
+            .visible
+    };
+    let far = 1_000_000_000_000;
+    assert_eq!(seen(far, 2, Direction::Up), seen(5, 2, Direction::Up));
+    assert_eq!(
+        seen(-far, -far, Direction::DownRight),
+        seen(-1, -1, Direction::DownRight)
+    );
+    assert_eq!(
+        seen(far + 2, -far, Direction::UpRight),
+        seen(7, -5, Direction::UpRight)
+    );
+    assert!(seen(far, 3, Direction::UpLeft).is_empty());
+    assert_eq!(
+        seen(isize::MAX, 2, Direction::Up),
+        seen(5, 2, Direction::Up)
+    );
+    assert_eq!(
+        seen(isize::MIN, isize::MIN, Direction::DownRight),
+        seen(-1, -1, Direction::DownRight)
+    );
+    assert_eq!(
+        seen(2, isize::MIN, Direction::Right),
+        seen(2, -1, Direction::Right)
+    );
+    assert!(seen(isize::MIN, isize::MAX, Direction::DownLeft).is_empty());
+    assert!(seen(isize::MAX, isize::MAX, Direction::Down).is_empty());
+}
+
+proptest! {
+    #[test]
+    fn orthogonal_distances_make_up_scenic_scores(grid in grid(10)) {
+        let scores = scenic_scores(&grid);
+        for coord in grid.coords() {
+            let score: usize = Direction::ORTHOGONAL
+                .into_iter()
+                .map(|direction| distance(&grid, Viewpoint::Tree(coord), direction))
+                .product();
+            prop_assert_eq!(score, scores[grid.index(coord)]);
+        }
+    }
+
+    // Lying on the ground just outside each edge and looking straight in sees
+    // exactly the trees the puzzle counts as visible.
+    #[test]
+    fn ground_observers_at_the_edges_match_visible_trees(grid in grid(10)) {
+        let (height, width) = (grid.height() as isize, grid.width() as isize);
+        let observers = (0..height)
+            .flat_map(|row| [(row, -1), (row, width)])
+            .chain((0..width).flat_map(|column| [(-1, column), (height, column)]));
+        let mut visible = vec![false; grid.len()];
+        for (row, column) in observers {
+            let observer = Viewpoint::Outside { row, column, height: -1 };
+            for sight in line_of_sight(&grid, observer).unwrap() {
+                if Direction::ORTHOGONAL.contains(&sight.direction) {
+                    for coord in sight.visible {
+                        visible[grid.index(coord)] = true;
+                    }
+                }
+            }
+        }
+        prop_assert_eq!(visible, visible_trees(&grid));
+    }
+
+    #[test]
+    fn visible_from_collects_every_direction(grid in grid(10), row in 0usize..10, column in 0usize..10) {
+        let coord = Coord::new(row % grid.height(), column % grid.width());
+        let visible = visible_from(&grid, Viewpoint::Tree(coord)).unwrap();
+        let count: usize = line_of_sight(&grid, Viewpoint::Tree(coord))
+            .unwrap()
+            .iter()
+            .map(|sight| sight.visible.len())
+            .sum();
+        prop_assert_eq!(visible.len(), count);
+        prop_assert!(!visible.contains(&coord));
+    }
+}